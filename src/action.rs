@@ -0,0 +1,379 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Everything the user can trigger, either from a key binding or the command palette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ClearLogs,
+    Reload,
+    FoldLogs,
+    SelectNext,
+    SelectPrevious,
+    SelectNewest,
+    SelectOldest,
+    ToggleAutoscroll,
+    /// Jumps to the newest log and turns autoscroll on, whatever the autoscroll mode
+    GoLive,
+    RevealSelection,
+    CenterSelection,
+    Filter,
+    /// Shows every log for a while, keeping the filter to go back to
+    ToggleFilter,
+    PickField,
+    DecreaseDetail,
+    IncreaseDetail,
+    ToggleWrap,
+    ToggleHexDump,
+    /// Lists what each parse step made of the selected log in LOG DETAILS
+    ToggleInspectParse,
+    ToggleJson,
+    ToggleDiff,
+    PanDetailsLeft,
+    PanDetailsRight,
+    ToggleLineNumbers,
+    ToggleTimeSort,
+    ToggleAgeFade,
+    ToggleTagGroups,
+    /// Collapses or expands the tag section of the selected row
+    ToggleGroup,
+    Yank,
+    /// Lists the copy formats to choose from
+    YankMenu,
+    YankTsv,
+    YankKeyValue,
+    YankStackTrace,
+    OpenSource,
+    Shell,
+    ToggleMouseCapture,
+    /// Overlays log counts, memory and frame time, for working on termlog itself
+    ToggleHud,
+    SavePreset,
+    LoadPreset,
+    /// Applies the preset in this quick slot, 0 being key `1`
+    RecallPreset(usize),
+    /// Searches the raw file, including text the parser dropped
+    GrepFile,
+    FocusNext,
+    CommandPalette,
+    /// Lists every key binding, which the footer has no room for
+    Help,
+}
+
+/// Keys listed by the help overlay, in listing order
+pub const KEY_HELP: &[(&str, &str)] = &[
+    ("j k ↑ ↓", "Move the selection; pick a field in LOG DETAILS"),
+    ("g G", "Jump to the newest / oldest log"),
+    ("a", "Autoscroll on/off"),
+    ("L", "Go live: newest log, autoscroll on"),
+    ("z Z", "Scroll to / center the selection"),
+    ("/", "Filter (field:value, AND/OR/NOT)"),
+    ("u", "Filter off/on, keeping it"),
+    ("o", "Filter by origin or tag"),
+    ("P p 1-9", "Save / load / recall a filter preset"),
+    ("[ ]", "Less / more detail in LOGS"),
+    ("w", "Wrap on/off"),
+    ("h l ← →", "Pan unwrapped LOG DETAILS"),
+    ("x", "Hex dump in LOG DETAILS"),
+    ("i", "Parse steps in LOG DETAILS"),
+    ("F", "Format JSON in LOG DETAILS"),
+    ("d", "Diff with the previously selected log"),
+    ("#", "Line numbers"),
+    ("s", "Sort by time"),
+    ("t Space", "Group by tag / collapse a group"),
+    ("y", "Yank the selection; the picked field in LOG DETAILS"),
+    ("Y", "Yank as…"),
+    ("T", "Yank the list as TSV"),
+    ("S", "Yank the stack trace"),
+    ("r", "Grep the raw file"),
+    ("e", "Open the log's file:line in $EDITOR"),
+    ("!", "Suspend to the shell"),
+    ("m", "Mouse capture on/off"),
+    ("H", "Performance HUD"),
+    ("Shift+Click", "Select a range of logs"),
+    ("Tab", "Focus the next panel"),
+    (":", "Command palette"),
+    ("c", "Clear logs"),
+    ("R", "Reload the file"),
+    ("f", "Fold repeated logs"),
+    ("?", "This list"),
+    ("q Esc", "Quit"),
+];
+
+impl Action {
+    /// Actions offered by the command palette, in listing order
+    pub const PALETTE: &'static [Action] = &[
+        Action::Filter,
+        Action::ToggleFilter,
+        Action::PickField,
+        Action::GrepFile,
+        Action::SavePreset,
+        Action::LoadPreset,
+        Action::SelectNewest,
+        Action::SelectOldest,
+        Action::ToggleAutoscroll,
+        Action::GoLive,
+        Action::CenterSelection,
+        Action::Yank,
+        Action::YankMenu,
+        Action::YankTsv,
+        Action::YankKeyValue,
+        Action::YankStackTrace,
+        Action::OpenSource,
+        Action::Shell,
+        Action::ToggleMouseCapture,
+        Action::ToggleHud,
+        Action::IncreaseDetail,
+        Action::DecreaseDetail,
+        Action::ToggleLineNumbers,
+        Action::ToggleTimeSort,
+        Action::ToggleTagGroups,
+        Action::ToggleGroup,
+        Action::ToggleAgeFade,
+        Action::ToggleWrap,
+        Action::ToggleHexDump,
+        Action::ToggleInspectParse,
+        Action::ToggleJson,
+        Action::ToggleDiff,
+        Action::FoldLogs,
+        Action::Help,
+        Action::Reload,
+        Action::ClearLogs,
+        Action::Quit,
+    ];
+
+    pub fn from_key(key: KeyEvent) -> Option<Self> {
+        let action = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Char('c') => Action::ClearLogs,
+            KeyCode::Char('R') => Action::Reload,
+            KeyCode::Char('f') => Action::FoldLogs,
+            KeyCode::Char('j') | KeyCode::Down => Action::SelectNext,
+            KeyCode::Char('k') | KeyCode::Up => Action::SelectPrevious,
+            KeyCode::Char('g') => Action::SelectNewest,
+            KeyCode::Char('G') => Action::SelectOldest,
+            KeyCode::Char('a') => Action::ToggleAutoscroll,
+            KeyCode::Char('L') => Action::GoLive,
+            KeyCode::Char('z') => Action::RevealSelection,
+            KeyCode::Char('Z') => Action::CenterSelection,
+            KeyCode::Char('/') => Action::Filter,
+            KeyCode::Char('u') => Action::ToggleFilter,
+            KeyCode::Char('o') => Action::PickField,
+            KeyCode::Char('[') => Action::DecreaseDetail,
+            KeyCode::Char(']') => Action::IncreaseDetail,
+            KeyCode::Char('w') => Action::ToggleWrap,
+            KeyCode::Char('x') => Action::ToggleHexDump,
+            KeyCode::Char('i') => Action::ToggleInspectParse,
+            KeyCode::Char('F') => Action::ToggleJson,
+            KeyCode::Char('d') => Action::ToggleDiff,
+            KeyCode::Char('h') | KeyCode::Left => Action::PanDetailsLeft,
+            KeyCode::Char('l') | KeyCode::Right => Action::PanDetailsRight,
+            KeyCode::Char('#') => Action::ToggleLineNumbers,
+            KeyCode::Char('s') => Action::ToggleTimeSort,
+            KeyCode::Char('t') => Action::ToggleTagGroups,
+            KeyCode::Char(' ') => Action::ToggleGroup,
+            KeyCode::Char('y') => Action::Yank,
+            KeyCode::Char('Y') => Action::YankMenu,
+            KeyCode::Char('T') => Action::YankTsv,
+            KeyCode::Char('S') => Action::YankStackTrace,
+            KeyCode::Char('r') => Action::GrepFile,
+            KeyCode::Char('e') => Action::OpenSource,
+            KeyCode::Char('!') => Action::Shell,
+            KeyCode::Char('m') => Action::ToggleMouseCapture,
+            KeyCode::Char('H') => Action::ToggleHud,
+            KeyCode::Char('P') => Action::SavePreset,
+            KeyCode::Char('p') => Action::LoadPreset,
+            KeyCode::Char(c @ '1'..='9') => Action::RecallPreset(c as usize - '1' as usize),
+            KeyCode::Tab => Action::FocusNext,
+            KeyCode::Char(':') => Action::CommandPalette,
+            KeyCode::Char('?') => Action::Help,
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::ClearLogs => "Clear Logs",
+            Action::Reload => "Reload File",
+            Action::FoldLogs => "Fold Logs",
+            Action::SelectNext => "Select Next",
+            Action::SelectPrevious => "Select Previous",
+            Action::SelectNewest => "Jump to Newest",
+            Action::SelectOldest => "Jump to Oldest",
+            Action::ToggleAutoscroll => "Toggle Autoscroll",
+            Action::GoLive => "Go Live",
+            Action::RevealSelection => "Scroll to Selection",
+            Action::CenterSelection => "Center Selection",
+            Action::Filter => "Filter",
+            Action::ToggleFilter => "Toggle Filter On/Off",
+            Action::PickField => "Filter by Origin/Tag",
+            Action::DecreaseDetail => "Less Detail",
+            Action::IncreaseDetail => "More Detail",
+            Action::ToggleWrap => "Toggle Wrap",
+            Action::ToggleHexDump => "Toggle Hex Dump",
+            Action::ToggleInspectParse => "Toggle Parse Inspector",
+            Action::ToggleJson => "Toggle JSON Formatting",
+            Action::ToggleDiff => "Toggle Diff with Previous",
+            Action::PanDetailsLeft => "Pan Details Left",
+            Action::PanDetailsRight => "Pan Details Right",
+            Action::ToggleLineNumbers => "Toggle Line Numbers",
+            Action::ToggleTimeSort => "Toggle Sort by Time",
+            Action::ToggleTagGroups => "Toggle Group by Tag",
+            Action::ToggleAgeFade => "Toggle Age Fade",
+            Action::ToggleGroup => "Collapse/Expand Group",
+            Action::Yank => "Yank Log",
+            Action::YankMenu => "Yank As…",
+            Action::YankTsv => "Yank Visible Logs as TSV",
+            Action::YankKeyValue => "Yank as key=value",
+            Action::YankStackTrace => "Yank Stack Trace",
+            Action::OpenSource => "Open Source Location",
+            Action::Shell => "Suspend to Shell",
+            Action::ToggleMouseCapture => "Toggle Mouse Capture",
+            Action::ToggleHud => "Toggle Performance HUD",
+            Action::SavePreset => "Save Filter Preset",
+            Action::LoadPreset => "Load Filter Preset",
+            Action::RecallPreset(_) => "Recall Preset Slot",
+            Action::GrepFile => "Grep Raw File",
+            Action::FocusNext => "Focus Next Panel",
+            Action::CommandPalette => "Command Palette",
+            Action::Help => "Key Bindings",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Exit termlog",
+            Action::ClearLogs => "Drop every log read so far",
+            Action::Reload => "Re-read the whole log file from the start",
+            Action::FoldLogs => "Fold runs of identical logs into one xN row, or expand them",
+            Action::SelectNext => "Move the selection to the next log",
+            Action::SelectPrevious => "Move the selection to the previous log",
+            Action::SelectNewest => "Select the newest log and resume autoscroll",
+            Action::SelectOldest => "Select the oldest log",
+            Action::ToggleAutoscroll => "Follow new logs as they arrive, or stop",
+            Action::GoLive => "Jump to the newest log and follow new ones from there",
+            Action::RevealSelection => "Scroll the LOGS list back to the selected log",
+            Action::CenterSelection => "Scroll the selected log to the middle of the LOGS list",
+            Action::Filter => "Show only logs containing some text",
+            Action::ToggleFilter => "See all logs around a match, then return to the filter",
+            Action::PickField => "Show only logs from one origin or tag",
+            Action::DecreaseDetail => "Show fewer fields in the LOGS list",
+            Action::IncreaseDetail => "Show more fields in the LOGS list",
+            Action::ToggleWrap => "Wrap long lines and multi-line previews, or pan and cut them",
+            Action::ToggleHexDump => "Show the raw bytes of the log in LOG DETAILS",
+            Action::ToggleInspectParse => "Show how each parse step handled the log in LOG DETAILS",
+            Action::ToggleJson => "Pretty-print JSON contents in LOG DETAILS",
+            Action::ToggleDiff => "Highlight what changed since the previously selected log",
+            Action::PanDetailsLeft => "Scroll unwrapped LOG DETAILS left",
+            Action::PanDetailsRight => "Scroll unwrapped LOG DETAILS right",
+            Action::ToggleLineNumbers => "Show the position of each log in the file",
+            Action::ToggleTimeSort => "Order logs by timestamp instead of file position",
+            Action::ToggleTagGroups => "Show the LOGS list as one section per tag",
+            Action::ToggleAgeFade => "Dim logs the older they are than the newest one",
+            Action::ToggleGroup => "Fold the selected tag section down to its header row",
+            Action::Yank => "Copy the selected log to the clipboard",
+            Action::YankMenu => "Choose how to copy the selection; y copies that way after",
+            Action::YankTsv => "Copy the LOGS list as a tab-separated table",
+            Action::YankKeyValue => "Copy the selected logs as one key=value line each",
+            Action::YankStackTrace => "Copy just the stack trace in the selected log's content",
+            Action::OpenSource => "Open the file:line mentioned by the log in $EDITOR",
+            Action::Shell => "Leave termlog for $SHELL until it exits",
+            Action::ToggleMouseCapture => {
+                "Let the terminal select text with the mouse instead of termlog"
+            }
+            Action::ToggleHud => "Show log counts, memory use and frame time in a corner",
+            Action::SavePreset => "Save the filter, hidden levels and detail level by name",
+            Action::LoadPreset => "Pick a saved filter preset to apply or delete",
+            Action::RecallPreset(_) => "Apply the preset saved in a numbered slot",
+            Action::GrepFile => "Search the file's raw text, even what the parser dropped",
+            Action::FocusNext => "Move keyboard focus to the next panel",
+            Action::CommandPalette => "Search and run an action",
+            Action::Help => "List every key and what it does",
+        }
+    }
+}
+
+/// Palette actions whose name fuzzy-matches `query`, best match first
+pub fn matching_actions(query: &str) -> Vec<Action> {
+    let mut scored: Vec<(usize, Action)> = Action::PALETTE
+        .iter()
+        .filter_map(|action| fuzzy_score(query, action.name()).map(|score| (score, *action)))
+        .collect();
+    // Stable sort keeps the listing order among equally good matches
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, action)| action).collect()
+}
+
+/// Case-insensitive subsequence match. Lower scores are better: every skipped character
+/// between two matched ones costs a point, so "clr" prefers "Clear Logs" over "Toggle Line…"
+fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut first_match = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = candidate[pos..].iter().position(|c| *c == q)?;
+        if first_match.is_some() {
+            score += offset;
+        } else {
+            first_match = Some(pos + offset);
+        }
+        pos += offset + 1;
+    }
+
+    Some(score + first_match.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_query_lists_everything() {
+        assert_eq!(matching_actions(""), Action::PALETTE.to_vec());
+    }
+
+    #[test]
+    fn test_subsequence_match() {
+        assert_eq!(fuzzy_score("clr", "Clear Logs"), Some(2));
+        assert_eq!(fuzzy_score("xyz", "Clear Logs"), None);
+    }
+
+    #[test]
+    fn test_best_match_first() {
+        assert_eq!(matching_actions("yank").first(), Some(&Action::Yank));
+        assert_eq!(
+            matching_actions("line").first(),
+            Some(&Action::ToggleLineNumbers)
+        );
+    }
+
+    #[test]
+    fn test_key_help_lists_bound_keys() {
+        for (keys, _) in KEY_HELP {
+            for key in keys.split(' ') {
+                let code = match key {
+                    // A mouse gesture, handled by App itself
+                    "Shift+Click" => continue,
+                    "Space" => KeyCode::Char(' '),
+                    "Tab" => KeyCode::Tab,
+                    "Esc" => KeyCode::Esc,
+                    "↑" => KeyCode::Up,
+                    "↓" => KeyCode::Down,
+                    "←" => KeyCode::Left,
+                    "→" => KeyCode::Right,
+                    "1-9" => KeyCode::Char('1'),
+                    _ => KeyCode::Char(key.chars().next().unwrap()),
+                };
+                assert!(
+                    Action::from_key(KeyEvent::from(code)).is_some(),
+                    "{} is listed but not bound",
+                    key
+                );
+            }
+        }
+    }
+}
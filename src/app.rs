@@ -1,73 +1,440 @@
 use crate::{
+    action::{self, Action},
     app_block::AppBlock,
-    content_line_maker::wrap_content_to_lines,
+    config::{AutoscrollMode, Config, EmptyContent, RepeatedTime},
+    content_line_maker::{
+        DiffOp, diff_lines, hex_dump_lines, highlight_matches, truncated_line,
+        wrap_content_to_lines, wrap_ranges,
+    },
     file_finder,
+    file_grep::{self, GrepMatch},
+    filter::{self, Field, FieldFilter, Query},
+    json,
     log_list::LogList,
-    log_parser::{LogItem, process_delta},
-    metadata, theme,
-    ui_logger::UiLogger,
+    log_parser::{
+        self, ColumnWidths, LogItem, LogParser, MAX_DETAIL_LEVEL, PreviewOptions, TailBuffer,
+    },
+    metadata,
+    preset::{Preset, Presets},
+    recorder::Recorder,
+    theme, tui,
+    ui_logger::{DebugRecord, UiLogger},
 };
 use anyhow::{Result, anyhow};
 use arboard::Clipboard;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
+use flate2::read::GzDecoder;
 use memmap2::MmapOptions;
 use ratatui::{
     Terminal,
     backend::CrosstermBackend,
     prelude::*,
-    widgets::{Padding, Paragraph, StatefulWidget, Widget},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, StatefulWidget, Widget},
 };
+use regex::Regex;
 use std::{
-    //collections::HashMap, // Removed - using direct fields instead
-    fs::File,
-    io,
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, Read, Write},
+    ops::Range,
     path::{Path, PathBuf},
+    process::Command,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
+use unicode_width::UnicodeWidthStr;
 
-pub fn start(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+/// Longest the main loop waits for input before checking its timers, so keys stay snappy
+/// however slowly the file is checked
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Redraw cadence without any input, so the spinner and other timed UI keep moving
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long the selection must stay on a log before copy-on-select copies it, so scrolling
+/// through the list doesn't copy every log passed on the way
+const COPY_ON_SELECT_SETTLE: Duration = Duration::from_millis(300);
+
+/// Most bytes parsed per tick, so a big file fills the list gradually instead of freezing
+/// the UI until all of it is parsed
+const LOAD_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Header fields of LOG DETAILS, in display order, that the details cursor moves over
+const DETAIL_FIELDS: [&str; 5] = ["Time", "Level", "Origin", "Tag", "Content"];
+
+/// How long a toast stays over the footer
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// Runs the TUI on `log_file`, or on the newest live log of the DouyinAR preview log
+/// directory when none is given
+pub fn start(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: Config,
+    log_file: Option<PathBuf>,
+) -> Result<()> {
     color_eyre::install().or(Err(anyhow!("Error installing color_eyre")))?;
 
-    // cd ~/Library/Application\ Support/DouyinAR/Logs/previewLog && open .
-    let log_dir_path = match dirs::home_dir() {
-        Some(path) => path.join("Library/Application Support/DouyinAR/Logs/previewLog"),
-        None => {
-            return Err(anyhow!("Error getting home directory"));
-        }
+    let log_dir_path = log_dir_for(log_file.as_deref())?;
+    let mut app = App::new(log_dir_path, log_file, &config)?;
+    app.config_path = Config::path();
+    if let Some(path) = Presets::path() {
+        app.presets = Presets::load(&path)?;
+        app.presets_path = Some(path);
+    }
+    app.run(terminal)
+}
+
+/// Writes the last `lines` parsed items of the log to `out` at the configured detail level
+/// and returns, without any TUI. The log is chosen the same way as in `start`.
+pub fn dump(
+    config: &Config,
+    log_file: Option<PathBuf>,
+    lines: usize,
+    out: &mut impl Write,
+) -> Result<()> {
+    let log_file_path = match log_file {
+        Some(path) => path,
+        None => file_finder::find_latest_live_log(
+            &log_dir_for(None)?,
+            config.live_log_regex()?.as_ref(),
+        )
+        .map_err(|e| anyhow!(e))?,
+    };
+
+    let bytes = if log_file_path.extension().is_some_and(|ext| ext == "gz") {
+        decompress(&log_file_path)?
+    } else {
+        fs::read(&log_file_path)
+            .map_err(|e| anyhow!("Failed to read '{}': {}", log_file_path.display(), e))?
     };
 
-    App::new(log_dir_path).run(terminal)
+    let detail_level = config.detail_level.unwrap_or(1).min(MAX_DETAIL_LEVEL);
+    let items = LogParser::new(config.format).process_delta(&String::from_utf8_lossy(&bytes));
+    for item in &items[items.len().saturating_sub(lines)..] {
+        writeln!(out, "{}", item.format_detail(detail_level))?;
+    }
+    Ok(())
+}
+
+/// Pads with spaces up to `width` terminal columns; `format!` padding counts chars, which
+/// falls short or overshoots for wide (CJK, emoji) and combining characters
+fn pad_to_width(text: String, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    text + &" ".repeat(padding)
+}
+
+fn wrap_label(wrap: bool) -> &'static str {
+    if wrap { "Wrap" } else { "No Wrap" }
+}
+
+/// Fingerprint of `text`, telling whether what was built from it still fits it
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `bytes` in B, KiB, MiB or GiB, one decimal past bytes
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// A line of pretty-printed JSON with its leading key highlighted
+fn json_line(line: &str) -> Line<'_> {
+    match json::key_end(line) {
+        Some(end) => Line::from(vec![
+            Span::styled(&line[..end], theme::JSON_KEY_STYLE),
+            Span::raw(&line[end..]),
+        ]),
+        None => Line::from(line),
+    }
+}
+
+/// Case-insensitive matcher of what filter `input` looks for in content, for highlighting it
+/// in LOG DETAILS. `None` when it looks for nothing there.
+fn highlight_regex(input: &str) -> Option<Regex> {
+    let query = Query::parse(input).unwrap_or_else(|_| Query::phrase(input));
+    let mut terms = query.content_terms();
+    // Longest first, so a term that another one starts with can't cut its match short
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+    let alternatives: Vec<String> = terms.iter().map(|term| regex::escape(term)).collect();
+    (!alternatives.is_empty())
+        .then(|| Regex::new(&format!("(?i){}", alternatives.join("|"))).ok())
+        .flatten()
+}
+
+/// A subtle centered row like `─── live ───` between the logs and the ends of the list
+fn marker_line(label: &str) -> Line<'static> {
+    Line::styled(format!("─── {} ───", label), theme::MARKER_STYLE).centered()
+}
+
+fn log_dir_for(log_file: Option<&Path>) -> Result<PathBuf> {
+    match log_file {
+        Some(file) => Ok(file.parent().map(Path::to_path_buf).unwrap_or_default()),
+        // cd ~/Library/Application\ Support/DouyinAR/Logs/previewLog && open .
+        None => match dirs::home_dir() {
+            Some(path) => Ok(path.join("Library/Application Support/DouyinAR/Logs/previewLog")),
+            None => Err(anyhow!("Error getting home directory")),
+        },
+    }
+}
+
+fn decompress(path: &Path) -> Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let mut bytes = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut bytes)
+        .map_err(|e| anyhow!("Failed to decompress '{}': {}", path.display(), e))?;
+    Ok(bytes)
+}
+
+/// Parses the bytes `prev_len..cur_len` of `file_path`. At most about `max_chunk` bytes
+/// are read per call, cut at an item boundary, so a large first load shows up piece by
+/// piece. The bytes go through `tail`, which holds what was read before them; returns how
+/// many earlier items the new ones replace (see `TailBuffer::feed`), the items and the
+/// offset read up to.
+fn map_and_process_delta(
+    parser: &LogParser,
+    tail: &mut TailBuffer,
+    file_path: &Path,
+    prev_len: u64,
+    cur_len: u64,
+    max_chunk: u64,
+) -> Result<(usize, Vec<LogItem>, u64)> {
+    let file = File::open(file_path)?;
+    let mmap = unsafe { MmapOptions::new().len(cur_len as usize).map(&file)? };
+
+    let start = (prev_len as usize).min(mmap.len());
+    let end = (cur_len as usize).min(mmap.len());
+    let mut delta_bytes = &mmap[start..end];
+
+    if delta_bytes.len() as u64 > max_chunk {
+        let window = &delta_bytes[..max_chunk as usize];
+        // A UTF-8 sequence may straddle the window edge; the valid prefix is enough to
+        // find a boundary in
+        let window = match std::str::from_utf8(window) {
+            Ok(text) => text,
+            Err(e) => std::str::from_utf8(&window[..e.valid_up_to()]).unwrap_or_default(),
+        };
+        // No boundary inside the window (one huge item): read the rest in one go
+        if let Some(cut) = parser.last_item_start(window) {
+            delta_bytes = &delta_bytes[..cut];
+        }
+    }
+
+    if delta_bytes.is_empty() {
+        return Ok((0, Vec::new(), cur_len));
+    }
+
+    let (replaced, log_items) = tail.feed(parser, delta_bytes);
+
+    Ok((replaced, log_items, (start + delta_bytes.len()) as u64))
+}
+
+/// What the yank menu can copy; the last one picked there is what `y` copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum YankFormat {
+    Markdown,
+    Raw,
+    KeyValue,
+    StackTrace,
+    Tsv,
+}
+
+impl YankFormat {
+    const ALL: [YankFormat; 5] = [
+        YankFormat::Markdown,
+        YankFormat::Raw,
+        YankFormat::KeyValue,
+        YankFormat::StackTrace,
+        YankFormat::Tsv,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            YankFormat::Markdown => "Markdown bundle",
+            YankFormat::Raw => "Raw text",
+            YankFormat::KeyValue => "key=value line",
+            YankFormat::StackTrace => "Stack trace",
+            YankFormat::Tsv => "Visible logs as TSV",
+        }
+    }
+}
+
+/// Keeps a TSV row on one line and every field in its column
+fn escape_tsv(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+/// The panel whose filter the filter prompt edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterTarget {
+    Logs,
+    Debug,
+}
+
+/// What the row above the logs in LOGS shows, when there is one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TopMarker {
+    /// Caught up and following new logs
+    Live,
+    /// The minute of the topmost log in view
+    StickyTime,
+}
+
+/// Wrapped line ranges of the item shown in LOG DETAILS, and what they were wrapped for
+struct WrapCache {
+    id: uuid::Uuid,
+    width: u16,
+    text_hash: u64, // So the ranges never outlive the text they index
+    ranges: Vec<Range<usize>>,
+    hidden: usize, // Lines past the wrap limit
+}
+
+/// Lines of the file shown around the highlighted match of a file grep
+const GREP_CONTEXT_LINES: usize = 2;
+
+/// The result list of a raw file search
+struct FileGrep {
+    pattern: String,
+    bytes: Vec<u8>, // The file as searched, for the lines around a match
+    matches: Vec<GrepMatch>,
+    selected: usize,
 }
 
 struct App {
     is_exiting: bool,
+    started_at: Instant, // Animation clock origin
     raw_logs: Vec<LogItem>,
+    parser: LogParser,
+    tail: TailBuffer, // The file's last item, parsed again when more of the file is read
     displaying_logs: LogList,
     log_dir_path: PathBuf,
+    live_log_pattern: Option<Regex>,
     log_file_path: PathBuf,
+    follow_latest: bool, // Switch to newer live logs as they appear (off for a given file)
+    is_archive: bool,    // log_file_path is compressed: read once, never tailed
     last_len: u64,
     prev_meta: Option<metadata::MetaSnap>,
     autoscroll: bool,
-    filter_mode: bool,                    // Whether we're in filter input mode
-    filter_input: String,                 // Current filter input text
-    detail_level: u8,                     // Detail level for log display (0-4, default 1)
-    debug_logs: Arc<Mutex<Vec<String>>>,  // Debug log messages for UI display
+    autoscroll_mode: AutoscrollMode,
+    autoscroll_held_off: bool, // Toggled off while at the top; Auto mode mustn't turn it back on
+    unread: usize,             // Logs shown since autoscroll paused; g/G or following again resets
+    show_unread: bool,         // Count them in the LOGS title
+    last_update_instant: Option<Instant>, // When update_logs last appended items
+    poll_backoff: metadata::PollBackoff, // Wait before run() checks the file again
+    load_progress: Option<(u64, u64)>, // Bytes read / file length while a large delta loads
+    recorder: Option<Recorder>, // Session recording sink for every parsed item
+    toast: Option<(String, Instant)>, // Brief feedback over the footer, until it expires
+    pending_command: Option<Command>, // Child to hand the terminal to, run from run()
+    mouse_capture: bool,       // Whether the app gets mouse events; run() applies changes
+    show_hud: bool,            // Overlay the performance HUD
+    last_frame_time: Option<Duration>, // How long the last terminal.draw took, timed in run()
+    header_title: String,      // Leads the header line
+    header_shows_file: bool,   // Follow header_title with the log file's name
+    filter_mode: bool,         // Whether we're in filter input mode
+    filter_input: String,      // Current filter input text
+    filter_before_edit: String, // Filter to restore if the input is cancelled
+    filter_suspended: bool,    // Show everything, keeping the filter for when it's back on
+    filter_target: FilterTarget, // Panel the open filter prompt edits, the focused one
+    debug_filter: String,      // Text the DEBUG LOGS entries must contain, ignoring case
+    filter_error: Option<String>, // Why filter_input doesn't parse, shown in the footer
+    field_filter: Option<FieldFilter>, // Exact origin/tag match chosen from the picker
+    hidden_levels: HashSet<&'static str>, // theme::LEVELS toggled off in the header legend
+    picker_mode: bool,         // Whether the origin/tag picker is open
+    picker_field: Field,       // Field whose values the picker lists
+    picker_selected: usize,    // Highlighted row; 0 is "All"
+    presets: Presets,          // Saved filter presets, in quick slot order
+    presets_path: Option<PathBuf>, // Where presets are saved; None keeps them in memory
+    preset_name_input: Option<String>, // Name being typed for the preset to save
+    preset_picker: Option<usize>, // Highlighted row while the preset list is open
+    grep_input: Option<String>, // Pattern being typed for a raw file search
+    file_grep: Option<FileGrep>, // Open result list of the last raw file search
+    yank_menu: Option<usize>,  // Highlighted row while the yank menu is open
+    help: Option<usize>,       // Highlighted row while the key list is open
+    yank_format: YankFormat,   // What `y` copies
+    palette_mode: bool,        // Whether the command palette is open
+    palette_input: String,     // Current command palette query
+    palette_selected: usize,   // Highlighted entry among the palette matches
+    detail_level: u8,          // Detail level for log display (0-MAX_DETAIL_LEVEL)
+    preview_options: PreviewOptions, // How content is shortened in the LOGS list
+    show_line_numbers: bool,   // Whether the LOGS list shows the raw_logs index gutter
+    show_sequence: bool,       // Lead LOGS rows with each item's parse order, `#seq`
+    next_seq: u64,             // Sequence number the next newly parsed item gets
+    repeated_time: RepeatedTime, // How a time in the same second as the row above is drawn
+    age_fade: bool,            // Dim LOGS rows the older they are than the newest log
+    sticky_time: bool,         // Show the minute of the topmost log in view above the list
+    selection_indicator: String, // Drawn before the selected row; may be empty
+    keep_selection_centered: bool, // ensure_selection_visible centers instead of nudging
+    center_margin: usize,      // Rows from an edge that trigger re-centering
+    scrolloff: usize,          // Context rows kept around the selection
+    wrap_around: bool,         // j/k past either end of LOGS continue from the other end
+    selection_style: Style,    // Highlight of the selected row
+    tag_levels: HashMap<String, String>, // Lowercase tag -> level whose color its rows take
+    sort_by_time: bool,        // Order the LOGS list by timestamp, not file position
+    skip_empty: bool,          // Leave logs with empty content out of the LOGS list
+    fold_threshold: u32,       // Identical logs in a row beyond which they're folded
+    fold_repeats: bool,        // Whether the LOGS list folds repeats at all
+    group_by_tag: bool,        // Show the LOGS list as one collapsible section per tag
+    collapsed_groups: HashSet<String>, // Tags whose section shows only its header row
+    group_ids: HashMap<String, uuid::Uuid>, // Header row id per tag, stable across rebuilds
+    group_headers: HashMap<uuid::Uuid, (String, usize)>, // Header rows shown: tag, member count
+    wrap: bool, // Wrap details content and the multi-line LOGS preview; pan/cut when off
+    config_path: Option<PathBuf>, // Where the wrap choice is saved; None doesn't save it
+    details_hex: bool, // Show raw_content as a hex dump in LOG DETAILS
+    details_inspect: bool, // Show the parse steps of the selected log in LOG DETAILS
+    details_diff: bool, // Diff LOG DETAILS against the previous selection
+    details_json: bool, // Pretty-print JSON contents in LOG DETAILS
+    details_field: usize, // DETAIL_FIELDS index of the details cursor
+    details_wrap_cache: Option<WrapCache>,
+    // Content of the item shown in LOG DETAILS pretty-printed, when it is JSON
+    details_json_cache: Option<(uuid::Uuid, Option<String>)>,
+    // Matcher of the filter's terms highlighted in LOG DETAILS, keyed by the filter text
+    details_highlight: Option<(String, Option<Regex>)>,
+    border_type: BorderType, // Corners of the panels, reused by the popups
+    debug_logs: Arc<Mutex<Vec<DebugRecord>>>, // Debug log messages for UI display
+    debug_timestamps: bool,  // Show the time each one was logged
     focused_block_id: Option<uuid::Uuid>, // Currently focused block ID
     logs_block: AppBlock,
     details_block: AppBlock,
     debug_block: AppBlock,
     prev_selected_log_id: Option<uuid::Uuid>, // Track previous selected log item ID for details reset
+    diff_base_id: Option<uuid::Uuid>,         // Item selected before prev_selected_log_id
     selected_log_uuid: Option<uuid::Uuid>,    // Track currently selected log item UUID
+    selection_anchor: Option<uuid::Uuid>,     // Other end of a Shift+Click range selection
+    copy_on_select: bool,                     // Copy the selected log once it settles
+    // Selection waiting to settle before copy-on-select copies it, and since when
+    pending_copy: Option<(uuid::Uuid, Instant)>,
+    copied_log_uuid: Option<uuid::Uuid>, // Last log copy-on-select copied
     last_logs_area: Option<Rect>, // Store the last rendered logs area for selection visibility
 
     event: Option<MouseEvent>,
 }
 
 impl App {
-    fn setup_logger() -> Arc<Mutex<Vec<String>>> {
+    fn setup_logger(debug_log_path: Option<&Path>) -> Result<Arc<Mutex<Vec<DebugRecord>>>> {
         let debug_logs = Arc::new(Mutex::new(Vec::new()));
-        let logger = Box::new(UiLogger::new(debug_logs.clone()));
+        let mut logger = UiLogger::new(debug_logs.clone());
+        if let Some(path) = debug_log_path {
+            logger = logger.with_file(path)?;
+        }
+        let logger = Box::new(logger);
 
         match log::set_logger(Box::leak(logger)) {
             Ok(_) => {
@@ -76,64 +443,216 @@ impl App {
             Err(_) => {}
         }
 
-        debug_logs
+        Ok(debug_logs)
     }
 
-    fn new(log_dir_path: PathBuf) -> Self {
-        let debug_logs = Self::setup_logger();
+    fn new(log_dir_path: PathBuf, log_file: Option<PathBuf>, config: &Config) -> Result<Self> {
+        let debug_logs = Self::setup_logger(config.debug_log_path.as_deref())?;
+        let live_log_pattern = config.live_log_regex()?;
+        let follow_latest = log_file.is_none();
 
         // Try to find the initial log file, but don't fail if none exists
-        let log_file_path = match file_finder::find_latest_live_log(&log_dir_path) {
-            Ok(path) => {
-                log::debug!("Found initial log file: {}", path.display());
-                path
-            }
-            Err(e) => {
-                log::debug!("No log files found initially: {}", e);
-                // Create a non-existent dummy path that will be replaced when a real log appears
-                log_dir_path.join("__no_log_file_yet__.log")
+        let log_file_path = match log_file {
+            Some(path) => path,
+            None => {
+                match file_finder::find_latest_live_log(&log_dir_path, live_log_pattern.as_ref()) {
+                    Ok(path) => {
+                        log::debug!("Found initial log file: {}", path.display());
+                        path
+                    }
+                    Err(e) => {
+                        log::debug!("No log files found initially: {}", e);
+                        // Create a non-existent dummy path that will be replaced when a real log appears
+                        log_dir_path.join("__no_log_file_yet__.log")
+                    }
+                }
             }
         };
+        let is_archive = log_file_path.extension().is_some_and(|ext| ext == "gz");
+        let recorder = match &config.record_path {
+            Some(path) => Some(Recorder::open(path, config.record_format)?),
+            None => None,
+        };
+
+        let (borders, border_type) = (config.borders.borders(), config.borders.border_type());
+        let focus_follows_mouse = config.focus_follows_mouse.unwrap_or(true);
 
-        Self {
+        Ok(Self {
             is_exiting: false,
+            started_at: Instant::now(),
             raw_logs: Vec::new(),
+            parser: LogParser::new(config.format),
+            tail: TailBuffer::default(),
             displaying_logs: LogList::new(Vec::new()),
             log_dir_path,
+            live_log_pattern,
             log_file_path,
+            follow_latest,
+            is_archive,
             last_len: 0,
             prev_meta: None,
             autoscroll: true,
+            autoscroll_mode: config.autoscroll_mode,
+            autoscroll_held_off: false,
+            unread: 0,
+            show_unread: config.show_unread.unwrap_or(true),
+            last_update_instant: None,
+            poll_backoff: metadata::PollBackoff::new(Duration::from_millis(
+                config.max_poll_interval_ms.unwrap_or(2000),
+            )),
+            load_progress: None,
+            recorder,
+            toast: None,
+            pending_command: None,
+            mouse_capture: true,
+            show_hud: false,
+            last_frame_time: None,
+            header_title: config
+                .title
+                .clone()
+                .unwrap_or_else(|| "Termlog".to_string()),
+            header_shows_file: config.title_shows_file,
             filter_mode: false,
             filter_input: String::new(),
-            detail_level: 1,
+            filter_before_edit: String::new(),
+            filter_suspended: false,
+            filter_target: FilterTarget::Logs,
+            debug_filter: String::new(),
+            filter_error: None,
+            field_filter: None,
+            hidden_levels: HashSet::new(),
+            picker_mode: false,
+            picker_field: Field::Origin,
+            picker_selected: 0,
+            presets: Presets::default(),
+            presets_path: None,
+            preset_name_input: None,
+            preset_picker: None,
+            grep_input: None,
+            file_grep: None,
+            help: None,
+            yank_menu: None,
+            yank_format: YankFormat::Markdown,
+            palette_mode: false,
+            palette_input: String::new(),
+            palette_selected: 0,
+            detail_level: config.detail_level.unwrap_or(1).min(MAX_DETAIL_LEVEL),
+            preview_options: config.preview,
+            show_line_numbers: false,
+            show_sequence: config.show_sequence,
+            next_seq: 0,
+            repeated_time: config.repeated_time,
+            age_fade: config.age_fade,
+            selection_indicator: config.selection.indicator().to_string(),
+            keep_selection_centered: config.center_selection,
+            center_margin: config.center_margin,
+            sticky_time: config.sticky_time,
+            scrolloff: config.scrolloff.unwrap_or(0),
+            wrap_around: config.wrap_around.unwrap_or(true),
+            selection_style: config.selection.style()?,
+            tag_levels: config.tag_levels()?,
+            sort_by_time: config.sort_by_time,
+            skip_empty: config.empty_content == EmptyContent::Skip,
+            fold_threshold: config.fold_threshold.unwrap_or(5),
+            fold_repeats: config.fold_threshold != Some(0),
+            group_by_tag: false,
+            collapsed_groups: HashSet::new(),
+            group_ids: HashMap::new(),
+            group_headers: HashMap::new(),
+            wrap: config.wrap.unwrap_or(true),
+            config_path: None,
+            details_hex: false,
+            details_inspect: false,
+            details_diff: false,
+            details_json: config.pretty_json,
+            details_field: 0,
+            details_wrap_cache: None,
+            details_json_cache: None,
+            details_highlight: None,
+            border_type,
             debug_logs,
+            debug_timestamps: config.debug_timestamps.unwrap_or(true),
             focused_block_id: None,
-            logs_block: AppBlock::new().set_title(format!("LOGS")),
+            logs_block: AppBlock::new()
+                .set_title(format!("LOGS"))
+                .set_borders(borders, border_type)
+                .set_focus_follows_mouse(focus_follows_mouse),
             details_block: AppBlock::new()
                 .set_title("LOG DETAILS")
+                .set_borders(borders, border_type)
+                .set_focus_follows_mouse(focus_follows_mouse)
                 .set_padding(Padding::horizontal(1)),
             debug_block: AppBlock::new()
                 .set_title("DEBUG LOGS")
+                .set_borders(borders, border_type)
+                .set_focus_follows_mouse(focus_follows_mouse)
                 .set_padding(Padding::horizontal(1)),
             prev_selected_log_id: None,
+            diff_base_id: None,
             selected_log_uuid: None,
+            selection_anchor: None,
+            copy_on_select: config.copy_on_select,
+            pending_copy: None,
+            copied_log_uuid: None,
             last_logs_area: None,
 
             event: None,
-        }
+        })
     }
 
     fn run(mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
         self.set_focused_block(self.logs_block.id());
 
-        let poll_interval = Duration::from_millis(100);
+        if self.is_archive {
+            self.load_compressed_log()?;
+        }
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> Result<()> {
+            let mut next_tick = Instant::now();
+            let mut next_file_poll = Instant::now();
+            let mut mouse_captured = true;
             while !self.is_exiting {
-                self.poll_event(poll_interval)?;
-                self.update_logs()?;
-                terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
+                // Input is read on its own short timeout; the redraw tick and the file checks
+                // each keep their own schedule. Don't wait for input between the chunks of a
+                // large load.
+                let timeout = match self.load_progress {
+                    Some(_) => Duration::ZERO,
+                    None => next_tick
+                        .min(next_file_poll)
+                        .saturating_duration_since(Instant::now())
+                        .min(INPUT_POLL_INTERVAL),
+                };
+                let mut redraw = self.poll_event(timeout)?;
+                if let Some(command) = self.pending_command.take() {
+                    self.run_outside_tui(terminal, command)?;
+                    mouse_captured = true; // Coming back enables it again
+                    redraw = true;
+                }
+                if self.mouse_capture != mouse_captured {
+                    tui::set_mouse_capture(self.mouse_capture)?;
+                    mouse_captured = self.mouse_capture;
+                }
+                if Instant::now() >= next_tick {
+                    next_tick = Instant::now() + REDRAW_INTERVAL;
+                    redraw = true;
+                }
+                if self.load_progress.is_some() || Instant::now() >= next_file_poll {
+                    self.poll_file()?;
+                    next_file_poll = Instant::now() + self.poll_backoff.interval();
+                    redraw = true;
+                }
+                self.flush_recording();
+                if let Some(text) = self.settled_copy(Instant::now()) {
+                    match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+                        Ok(()) => log::debug!("Copied the selected log"),
+                        Err(e) => log::warn!("Copy on select failed: {}", e),
+                    }
+                }
+                if redraw {
+                    let draw_started = Instant::now();
+                    terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
+                    self.last_frame_time = Some(draw_started.elapsed());
+                }
             }
             Ok(())
         }));
@@ -146,57 +665,77 @@ impl App {
         }
     }
 
-    fn poll_event(&mut self, poll_interval: Duration) -> Result<()> {
-        // Check for newer log files first
-        if let Ok(Some(newer_file)) = self.check_for_newer_log_file() {
+    /// Switches to a newer live log when following the latest, then reads what was appended
+    fn poll_file(&mut self) -> Result<()> {
+        if self.follow_latest
+            && let Ok(Some(newer_file)) = self.check_for_newer_log_file()
+        {
             self.switch_to_log_file(newer_file)?;
         }
+        self.update_logs()
+    }
 
-        if event::poll(poll_interval)? {
-            let event = event::read()?;
-            match event {
-                Event::Key(key) => self.handle_key(key)?,
-                Event::Mouse(mouse) => {
-                    match mouse.kind {
-                        MouseEventKind::ScrollDown => {
-                            if self.is_log_block_focused()? {
-                                self.handle_logs_view_scrolling(true)?;
-                            }
-                            if self.is_details_block_focused()? {
-                                self.handle_details_block_scrolling(true)?;
-                            }
-                            if self.is_debug_block_focused()? {
-                                self.handle_debug_logs_scrolling(true)?;
-                            }
+    /// Waits up to `timeout` for an input event and handles it. Returns whether there was one.
+    fn poll_event(&mut self, timeout: Duration) -> Result<bool> {
+        if !event::poll(timeout)? {
+            return Ok(false);
+        }
+        let event = event::read()?;
+        match event {
+            Event::Key(key) => self.handle_key(key)?,
+            Event::Mouse(mouse) => {
+                match mouse.kind {
+                    MouseEventKind::ScrollDown => {
+                        if self.is_log_block_focused()? {
+                            self.handle_logs_view_scrolling(true)?;
                         }
-                        MouseEventKind::ScrollUp => {
-                            if self.is_log_block_focused()? {
-                                self.handle_logs_view_scrolling(false)?;
-                            }
-                            if self.is_details_block_focused()? {
-                                self.handle_details_block_scrolling(false)?;
-                            }
-                            if self.is_debug_block_focused()? {
-                                self.handle_debug_logs_scrolling(false)?;
-                            }
+                        if self.is_details_block_focused()? {
+                            self.handle_details_block_scrolling(true)?;
                         }
-                        MouseEventKind::Moved => {
-                            // Mouse moved - the render methods will handle hover focus
-                            // Just store the event so blocks can check if mouse is hovering
+                        if self.is_debug_block_focused()? {
+                            self.handle_debug_logs_scrolling(true)?;
                         }
-                        _ => {}
                     }
-                    self.event = Some(mouse);
-                }
-                Event::Resize(width, height) => {
-                    // Terminal was resized, ratatui will handle the layout automatically
-                    log::debug!("Terminal resized to {}x{}", width, height);
+                    MouseEventKind::ScrollUp => {
+                        if self.is_log_block_focused()? {
+                            self.handle_logs_view_scrolling(false)?;
+                        }
+                        if self.is_details_block_focused()? {
+                            self.handle_details_block_scrolling(false)?;
+                        }
+                        if self.is_debug_block_focused()? {
+                            self.handle_debug_logs_scrolling(false)?;
+                        }
+                    }
+                    MouseEventKind::ScrollLeft if self.is_details_block_focused()? => {
+                        self.handle_details_h_scrolling(false)?;
+                    }
+                    MouseEventKind::ScrollRight if self.is_details_block_focused()? => {
+                        self.handle_details_h_scrolling(true)?;
+                    }
+                    MouseEventKind::Moved => {
+                        // Mouse moved - the render methods will handle hover focus
+                        // Just store the event so blocks can check if mouse is hovering
+                    }
+                    _ => {}
                 }
-                _ => {}
+                self.event = Some(mouse);
             }
+            Event::Paste(text) => self.handle_paste(&text),
+            Event::Resize(width, height) => {
+                // Terminal was resized, ratatui will handle the layout automatically
+                log::debug!("Terminal resized to {}x{}", width, height);
+            }
+            _ => {}
         }
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Frame number of an animation advancing every `frame_time`. Everything animated counts
+    /// from the same start, so spinners and fades stay in step across redraws.
+    fn animation_frame(&self, frame_time: Duration) -> usize {
+        (self.started_at.elapsed().as_millis() / frame_time.as_millis().max(1)) as usize
     }
 
     fn to_underlying_index(total: usize, visual_index: usize) -> usize {
@@ -207,8 +746,44 @@ impl App {
         total.saturating_sub(1).saturating_sub(underlying_index)
     }
 
+    /// Reads what was appended to the tailed file between the last read and its rotation,
+    /// from wherever it was moved to in the log directory, so no logs are lost across the
+    /// boundary. Returns whether anything was appended.
+    fn read_rotated_tail(&mut self, ino: u64) -> bool {
+        let Some(rotated_path) = file_finder::find_by_inode(&self.log_dir_path, ino) else {
+            log::debug!("Rotated log not found in the log directory");
+            return false;
+        };
+        let Ok(rotated_meta) = metadata::stat_path(&rotated_path) else {
+            return false;
+        };
+        if rotated_meta.len <= self.last_len {
+            return false;
+        }
+        match map_and_process_delta(
+            &self.parser,
+            &mut self.tail,
+            &rotated_path,
+            self.last_len,
+            rotated_meta.len,
+            u64::MAX,
+        ) {
+            Ok((replaced, new_items, _)) => {
+                log::debug!(
+                    "Found {} log items written before rotation to file://{}",
+                    new_items.len().saturating_sub(replaced),
+                    rotated_path.display().to_string().replace(" ", "%20")
+                );
+                self.append_logs(replaced, new_items);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     fn check_for_newer_log_file(&self) -> Result<Option<PathBuf>> {
-        match file_finder::find_latest_live_log(&self.log_dir_path) {
+        match file_finder::find_latest_live_log(&self.log_dir_path, self.live_log_pattern.as_ref())
+        {
             Ok(latest_file_path) => {
                 // Check if we currently have no valid log file (first time finding one)
                 if !self.log_file_path.exists() {
@@ -247,6 +822,7 @@ impl App {
         // Switch to new file
         self.log_file_path = new_file_path;
         self.last_len = 0;
+        self.tail = TailBuffer::default();
         self.prev_meta = None;
 
         // Reset logs but preserve UI state
@@ -265,14 +841,49 @@ impl App {
 
         // Clear selection tracking
         self.selected_log_uuid = None;
+        self.selection_anchor = None;
+        self.prev_selected_log_id = None;
+
+        Ok(())
+    }
+
+    /// Drops everything parsed so far and reads the file again from the start, keeping the
+    /// filter. The selection goes back to the newest item.
+    fn reload_logs(&mut self) -> Result<()> {
+        log::debug!(
+            "Reloading file://{}",
+            self.log_file_path.display().to_string().replace(" ", "%20")
+        );
+
+        self.last_len = 0;
+        self.tail = TailBuffer::default();
+        self.prev_meta = None;
+        self.raw_logs.clear();
+        self.unread = 0;
+        self.displaying_logs = LogList::new(Vec::new());
+        self.selected_log_uuid = None;
+        self.selection_anchor = None;
         self.prev_selected_log_id = None;
+        self.diff_base_id = None;
+        self.logs_block.set_scroll_position(0);
+        self.details_block.set_scroll_position(0);
+        self.autoscroll = true;
+
+        if self.is_archive {
+            self.load_compressed_log()?;
+        } else {
+            self.update_logs()?;
+        }
+        self.displaying_logs.select_first();
+        self.update_selected_uuid();
 
+        log::debug!("Reloaded {} log items", self.raw_logs.len());
         Ok(())
     }
 
     fn update_logs(&mut self) -> Result<()> {
-        // Skip update if we don't have a valid log file yet
-        if !self.log_file_path.exists() {
+        // Skip update if we don't have a valid log file yet, or if it is never tailed
+        if self.is_archive || !self.log_file_path.exists() {
             return Ok(());
         }
 
@@ -284,103 +895,223 @@ impl App {
             }
         };
 
-        if metadata::has_changed(&self.prev_meta, &current_meta) {
-            if current_meta.len < self.last_len {
+        let changed = metadata::has_changed(&self.prev_meta, &current_meta);
+        self.poll_backoff.record(changed);
+        if changed {
+            let mut was_reset = false;
+            let mut was_rebuilt = false;
+            // A new file at the same path: the old one was rotated away, possibly with logs
+            // written after the last read
+            let rotated_ino = self
+                .prev_meta
+                .as_ref()
+                .map(|prev| prev.ino)
+                .filter(|ino| *ino != current_meta.ino);
+            if let Some(ino) = rotated_ino {
+                was_rebuilt = self.read_rotated_tail(ino);
+            }
+            if rotated_ino.is_some() || current_meta.len < self.last_len {
                 // File truncated/rotated: reset read offset but keep current UI state
                 self.last_len = 0;
+                self.tail = TailBuffer::default();
+                was_reset = true;
             }
 
             if current_meta.len > self.last_len {
-                if let Ok(new_items) =
-                    map_and_process_delta(&self.log_file_path, self.last_len, current_meta.len)
-                {
-                    let old_items_count = self.displaying_logs.items.len();
-                    let previous_uuid = self.selected_log_uuid;
-                    let previous_scroll_pos = Some(self.logs_block.get_scroll_position());
-
-                    log::debug!(
-                        "Found {} new log items in file://{}",
-                        new_items.len(),
-                        self.log_file_path.display().to_string().replace(" ", "%20")
-                    );
-                    self.raw_logs.extend(new_items);
-
-                    // Rebuild displayed logs (respect filter)
-                    if self.filter_input.is_empty() {
-                        self.displaying_logs = LogList::new(self.raw_logs.clone());
-                    } else {
-                        // Re-apply filter without losing selection
-                        self.rebuild_filtered_list();
-                    }
-
-                    // Restore selection via UUID (no index math)
-                    if previous_uuid.is_some() {
-                        self.update_selection_by_uuid();
-                    } else if self.autoscroll {
-                        // No selection -> optionally keep newest selected when autoscroll is ON
-                        self.displaying_logs.select_first();
-                        self.update_selected_uuid();
-                    }
-
-                    // Adjust scroll to keep visible content stable if autoscroll is OFF
-                    {
-                        let new_items_count = self.displaying_logs.items.len();
-                        let items_added = new_items_count.saturating_sub(old_items_count);
-
-                        if self.autoscroll {
-                            self.logs_block.set_scroll_position(0);
-                        } else if let Some(prev) = previous_scroll_pos {
-                            // Because newest is at visual index 0, adding items pushes
-                            // existing content down; keep the same lines visible by shifting
-                            // the top by items_added.
-                            let new_scroll_pos = prev.saturating_add(items_added);
-                            let max_top = new_items_count.saturating_sub(1);
-                            self.logs_block
-                                .set_scroll_position(new_scroll_pos.min(max_top));
-                        }
-
-                        self.logs_block.set_lines_count(new_items_count);
-                        self.logs_block.update_scrollbar_state(
-                            new_items_count,
-                            Some(self.logs_block.get_scroll_position()),
+                match map_and_process_delta(
+                    &self.parser,
+                    &mut self.tail,
+                    &self.log_file_path,
+                    self.last_len,
+                    current_meta.len,
+                    LOAD_CHUNK_BYTES,
+                ) {
+                    Ok((replaced, new_items, read_to)) => {
+                        was_rebuilt = true;
+                        log::debug!(
+                            "Found {} new log items in file://{}",
+                            new_items.len().saturating_sub(replaced),
+                            self.log_file_path.display().to_string().replace(" ", "%20")
                         );
+                        if !new_items.is_empty() {
+                            self.last_update_instant = Some(Instant::now());
+                        }
+                        self.append_logs(replaced, new_items);
+                        self.last_len = read_to;
                     }
+                    Err(_) => self.last_len = current_meta.len,
                 }
-                self.last_len = current_meta.len;
             }
 
-            self.prev_meta = Some(current_meta);
+            if was_reset && !was_rebuilt {
+                // Nothing was re-read after the reset, but the filtered view must still
+                // be rebuilt from raw_logs so the two never drift apart
+                self.apply_filter(false);
+            }
+
+            if self.last_len < current_meta.len {
+                // Only a chunk of a large delta was read: leave prev_meta behind so the
+                // next tick carries on from last_len
+                self.load_progress = Some((self.last_len, current_meta.len));
+            } else {
+                self.load_progress = None;
+                self.prev_meta = Some(current_meta);
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends freshly parsed items to raw_logs and refreshes the displayed list, keeping the
+    /// selection and (when autoscroll is off) the visible lines stable
+    fn append_logs(&mut self, replaced: usize, mut new_items: Vec<LogItem>) {
+        // The first `replaced` items are the last ones of raw_logs parsed again, maybe with
+        // more lines now. They keep their ids, so a selection stays on them, and replace
+        // what was recorded of them.
+        let kept = self.raw_logs.len().saturating_sub(replaced);
+        for (old, new) in self.raw_logs[kept..].iter().zip(new_items.iter_mut()) {
+            new.id = old.id;
+            new.seq = old.seq;
+            // LOG DETAILS caches by id, so drop what it built from the old text
+            if self
+                .details_json_cache
+                .as_ref()
+                .is_some_and(|(id, _)| *id == old.id)
+            {
+                self.details_json_cache = None;
+            }
+            if self
+                .details_wrap_cache
+                .as_ref()
+                .is_some_and(|cache| cache.id == old.id)
+            {
+                self.details_wrap_cache = None;
+            }
+        }
+        for new in new_items.iter_mut().skip(replaced) {
+            new.seq = self.next_seq;
+            self.next_seq += 1;
         }
-        return Ok(());
+        self.record_logs(replaced, &new_items);
 
-        fn map_and_process_delta(
-            file_path: &Path,
-            prev_len: u64,
-            cur_len: u64,
-        ) -> Result<Vec<LogItem>> {
-            let file = File::open(file_path)?;
-            let mmap = unsafe { MmapOptions::new().len(cur_len as usize).map(&file)? };
+        let old_items_count = self.displaying_logs.items.len();
+        let previous_uuid = self.selected_log_uuid;
+        let previous_scroll_pos = Some(self.logs_block.get_scroll_position());
+
+        self.raw_logs.truncate(kept);
+        let base_index = self.raw_logs.len();
+        let first_new_index = base_index + replaced.min(new_items.len());
+        self.raw_logs
+            .extend(new_items.into_iter().enumerate().map(|(i, mut item)| {
+                item.index = base_index + i;
+                item
+            }));
+
+        // Rebuild displayed logs (respect filter and sort); selection is restored below
+        self.rebuild_filtered_list();
+        // With a filter active the delta may hold nothing to show, in which case autoscroll
+        // has nothing to follow and the view stays put
+        let new_items_shown = self
+            .displaying_logs
+            .items
+            .iter()
+            .filter(|item| {
+                item.index >= first_new_index && !self.group_headers.contains_key(&item.id)
+            })
+            .count();
+        let shows_new_items = new_items_shown > 0;
+        if !self.autoscroll {
+            self.unread += new_items_shown;
+        }
 
-            let start = (prev_len as usize).min(mmap.len());
-            let end = (cur_len as usize).min(mmap.len());
-            let delta_bytes = &mmap[start..end];
+        // Restore selection via UUID (no index math)
+        if previous_uuid.is_some() {
+            self.update_selection_by_uuid();
+        }
+        if self.displaying_logs.state.selected().is_none() && self.autoscroll && shows_new_items {
+            // No selection, or the selected log is gone (cleared, filtered out) -> keep
+            // following the newest item when autoscroll is ON
+            self.displaying_logs.select_first();
+            self.update_selected_uuid();
+        }
 
-            if delta_bytes.is_empty() {
-                return Ok(Vec::new());
+        // Adjust scroll to keep visible content stable if autoscroll is OFF
+        {
+            let new_items_count = self.displaying_logs.items.len();
+            let items_added = new_items_count.saturating_sub(old_items_count);
+
+            if self.autoscroll && shows_new_items {
+                self.logs_block.set_scroll_position(0);
+            } else if let Some(prev) = previous_scroll_pos {
+                // Because newest is at visual index 0, adding items pushes
+                // existing content down; keep the same lines visible by shifting
+                // the top by items_added.
+                let new_scroll_pos = prev.saturating_add(items_added);
+                let max_top = new_items_count.saturating_sub(1);
+                self.logs_block
+                    .set_scroll_position(new_scroll_pos.min(max_top));
             }
 
-            let delta_str = String::from_utf8_lossy(delta_bytes);
-            let log_items = process_delta(&delta_str);
+            self.logs_block.set_lines_count(new_items_count);
+            self.logs_block.update_scrollbar_state(
+                new_items_count,
+                Some(self.logs_block.get_scroll_position()),
+            );
+        }
+    }
+
+    fn record_logs(&mut self, replaced: usize, items: &[LogItem]) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.record(replaced, items)
+        {
+            log::debug!("Session recording stopped: {}", e);
+            self.recorder = None;
+        }
+    }
 
-            Ok(log_items)
+    fn flush_recording(&mut self) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.flush_if_due()
+        {
+            log::debug!("Session recording stopped: {}", e);
+            self.recorder = None;
         }
     }
 
-    fn apply_filter(&mut self) {
+    /// Reads a compressed (rotated) log once; it is never tailed afterwards
+    fn load_compressed_log(&mut self) -> Result<()> {
+        let bytes = decompress(&self.log_file_path)?;
+        let new_items = self.parser.process_delta(&String::from_utf8_lossy(&bytes));
+        log::debug!(
+            "Loaded {} log items from archived file://{}",
+            new_items.len(),
+            self.log_file_path.display().to_string().replace(" ", "%20")
+        );
+        self.append_logs(0, new_items);
+        Ok(())
+    }
+
+    /// Re-filters `displaying_logs` from `filter_input`. When `narrowing` is set the query only
+    /// grew since the current list was built, so the list is refined instead of rescanning
+    /// `raw_logs`.
+    fn apply_filter(&mut self, narrowing: bool) {
         let previous_uuid = self.selected_log_uuid;
         let prev_scroll_pos = Some(self.logs_block.get_scroll_position());
 
-        self.rebuild_filtered_list();
+        // Narrowing keeps items in place, which would leave stale group and fold counts
+        if narrowing
+            && !self.group_by_tag
+            && !self
+                .displaying_logs
+                .items
+                .iter()
+                .any(|item| item.folded_count > 1)
+            && !self.filter_input.is_empty()
+            && self.parse_filter().is_plain()
+        {
+            self.narrow_filtered_list();
+        } else {
+            self.rebuild_filtered_list();
+        }
 
         // Restore selection via UUID if possible
         if previous_uuid.is_some() {
@@ -407,85 +1138,506 @@ impl App {
 
     // Helper used by update_logs/apply_filter to rebuild displayed logs
     fn rebuild_filtered_list(&mut self) {
-        if self.filter_input.is_empty() {
-            self.displaying_logs = LogList::new(self.raw_logs.clone());
+        let mut items: Vec<LogItem> = if !self.is_filtering() {
+            self.filter_error = None;
+            self.raw_logs.clone()
         } else {
-            let filtered_items: Vec<LogItem> = self
-                .raw_logs
+            let query = self.parse_filter();
+            self.raw_logs
                 .iter()
-                .filter(|item| item.contains(&self.filter_input))
+                .filter(|item| self.matches_filter(&query, item))
                 .cloned()
-                .collect();
-            self.displaying_logs = LogList::new(filtered_items);
+                .collect()
+        };
+        if self.skip_empty {
+            items.retain(|item| !item.has_empty_content());
+        }
+        if self.sort_by_time {
+            log_parser::sort_by_time(&mut items);
+        }
+        if self.fold_repeats && self.fold_threshold > 0 {
+            items = log_parser::fold_repeats(items, self.fold_threshold);
         }
+        if self.group_by_tag {
+            items = self.group_items_by_tag(items);
+        } else {
+            self.group_headers.clear();
+        }
+        self.displaying_logs = LogList::new(items);
     }
 
-    fn exit_filter_mode(&mut self) {
-        self.filter_mode = false;
-        self.filter_input.clear();
-        // Reset to show all logs
-        self.displaying_logs = LogList::new(self.raw_logs.clone());
-        self.displaying_logs.select_first();
+    /// Reorders `items` into one section per tag, the section with the newest item first.
+    /// Each section gets a synthesized header row, drawn above its members, so selection,
+    /// clicks and scrolling treat headers as ordinary rows. Members of collapsed sections
+    /// are left out.
+    fn group_items_by_tag(&mut self, items: Vec<LogItem>) -> Vec<LogItem> {
+        let mut groups: Vec<(String, Vec<LogItem>)> = Vec::new();
+        let mut group_of_tag: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            let group = *group_of_tag.entry(item.tag.clone()).or_insert_with(|| {
+                groups.push((item.tag.clone(), Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].1.push(item);
+        }
+        // Underlying order is oldest first, so the section holding the newest item goes last
+        let position_of = |members: &[LogItem]| members.last().map_or(0, |item| item.index);
+        if self.sort_by_time {
+            groups.sort_by(|(_, a), (_, b)| {
+                a.last()
+                    .and_then(|item| item.timestamp)
+                    .cmp(&b.last().and_then(|item| item.timestamp))
+            });
+        } else {
+            groups.sort_by_key(|(_, members)| position_of(members));
+        }
+
+        self.group_headers.clear();
+        let mut grouped = Vec::new();
+        for (tag, members) in groups {
+            let id = *self
+                .group_ids
+                .entry(tag.clone())
+                .or_insert_with(uuid::Uuid::new_v4);
+            let count = members.len();
+            // Header rows sit above their members, i.e. after them in underlying order
+            let header = LogItem {
+                id,
+                time: String::new(),
+                timestamp: None,
+                level: String::new(),
+                origin: String::new(),
+                tag: tag.clone(),
+                content: format!("{} log{}", count, if count == 1 { "" } else { "s" }),
+                raw_content: String::new(),
+                original: String::new(),
+                folded_count: 1,
+                index: members.last().map_or(0, |item| item.index),
+                seq: members.last().map_or(0, |item| item.seq),
+            };
+            if !self.collapsed_groups.contains(&tag) {
+                grouped.extend(members);
+            }
+            grouped.push(header);
+            self.group_headers.insert(id, (tag, count));
+        }
+        grouped
     }
 
-    fn update_logs_scrollbar_state(&mut self) {
-        let total = self.displaying_logs.items.len();
+    /// Collapses or expands the tag section of the selected row, keeping the selection on
+    /// its header
+    fn toggle_selected_group(&mut self) {
+        let (items, state) = (&self.displaying_logs.items, &self.displaying_logs.state);
+        let Some(selected) = state.selected() else {
+            return;
+        };
+        let tag = items[App::to_underlying_index(items.len(), selected)]
+            .tag
+            .clone();
+        if !self.collapsed_groups.remove(&tag) {
+            self.collapsed_groups.insert(tag.clone());
+        }
+        self.selected_log_uuid = self.group_ids.get(&tag).copied();
+        self.apply_filter(false);
+    }
 
-        {
-            // Clamp position to valid range
-            let max_top = total.saturating_sub(1);
-            let pos = self.logs_block.get_scroll_position().min(max_top);
-            self.logs_block.set_scroll_position(pos);
+    // Every item matching the extended query also matched the previous one, so only the
+    // currently displayed items need checking
+    fn narrow_filtered_list(&mut self) {
+        let query = self.parse_filter();
+        let narrowed_items: Vec<LogItem> = self
+            .displaying_logs
+            .items
+            .iter()
+            .filter(|item| self.matches_filter(&query, item))
+            .cloned()
+            .collect();
+        self.displaying_logs = LogList::new(narrowed_items);
+    }
 
-            self.logs_block.set_lines_count(total);
-            self.logs_block.update_scrollbar_state(total, Some(pos));
+    /// Parses filter_input, recording any error for the footer. An invalid expression
+    /// (often one still being typed) matches as a plain phrase until it is fixed.
+    fn parse_filter(&mut self) -> Query {
+        match Query::parse(&self.filter_input) {
+            Ok(query) => {
+                self.filter_error = None;
+                query
+            }
+            Err(e) => {
+                self.filter_error = Some(e);
+                Query::phrase(&self.filter_input)
+            }
         }
     }
 
-    fn render_header(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
-        let autoscroll_status = if self.autoscroll { "ON" } else { "OFF" };
-        let title = format!("Termlog | Autoscroll {}", autoscroll_status);
-        Paragraph::new(title).bold().centered().render(area, buf);
-        Ok(())
+    fn is_filtering(&self) -> bool {
+        self.has_filter() && !self.filter_suspended
     }
 
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
-        let help_text = if self.filter_mode {
-            format!(
-                "Filter: {} (Press Enter to apply, Esc to cancel)",
-                self.filter_input
-            )
-        } else {
-            "jk↑↓: nav | gG: top/bottom | /: filter | []: detail | y: yank | JK: scroll focused | c: clear | f: fold | q: quit"
-                .to_string()
-        };
-        Paragraph::new(help_text).centered().render(area, buf);
-        Ok(())
+    /// Whether any filter is set, switched off or not
+    fn has_filter(&self) -> bool {
+        !self.filter_input.is_empty()
+            || self.field_filter.is_some()
+            || !self.hidden_levels.is_empty()
     }
 
-    fn render_logs(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
-        // Store the area for selection visibility calculations
-        self.last_logs_area = Some(area);
+    fn matches_filter(&self, query: &Query, item: &LogItem) -> bool {
+        query.matches(item)
+            && !self.hidden_levels.contains(item.level.as_str())
+            && self
+                .field_filter
+                .as_ref()
+                .is_none_or(|field_filter| field_filter.matches(item))
+    }
 
-        // Create a horizontal layout: main content area + scrollbar area
-        let [content_area, scrollbar_area] = Layout::horizontal([
-            Constraint::Fill(1),   // Main content takes most space
-            Constraint::Length(1), // Scrollbar is 1 character wide
-        ])
-        .margin(0)
-        .areas(area);
+    /// Rows offered by the picker after the leading "All" row
+    fn picker_values(&self) -> Vec<(String, usize)> {
+        filter::distinct_values(&self.raw_logs, self.picker_field)
+    }
 
-        let is_log_focused = self.is_log_block_focused().unwrap_or(false);
+    /// Searches the file's raw text for `pattern` and lists the matching lines, so text the
+    /// parser dropped can be found too
+    fn grep_file(&mut self, pattern: String) {
+        let bytes = if self.is_archive {
+            decompress(&self.log_file_path)
+        } else {
+            fs::read(&self.log_file_path)
+                .map_err(|e| anyhow!("Failed to read '{}': {}", self.log_file_path.display(), e))
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.toast(e.to_string());
+                return;
+            }
+        };
+        let matches = file_grep::grep(&bytes, &pattern);
+        if matches.is_empty() {
+            self.toast(format!("No line of the file contains '{}'", pattern));
+            return;
+        }
+        self.file_grep = Some(FileGrep {
+            pattern,
+            bytes,
+            matches,
+            selected: 0,
+        });
+    }
 
-        // Get and update the LOGS block (title, mouse focus)
+    /// Closes the grep results and selects the log the highlighted line belongs to, or the
+    /// closest one above it
+    fn jump_to_grep_match(&mut self) -> Result<()> {
+        let Some(grep) = self.file_grep.take() else {
+            return Ok(());
+        };
+        let line_number = grep.matches[grep.selected].line_number;
+        let Some(index) = file_grep::nearest_item(&grep.bytes, line_number, &self.raw_logs) else {
+            self.toast(format!("No parsed log at or above line {}", line_number));
+            return Ok(());
+        };
+        let id = self.raw_logs[index].id;
+        if !self.displaying_logs.items.iter().any(|item| item.id == id) {
+            self.toast(format!(
+                "The log at line {} isn't shown in LOGS (filtered or folded)",
+                line_number
+            ));
+            return Ok(());
+        }
+        self.selected_log_uuid = Some(id);
+        self.update_selection_by_uuid();
+        self.selection_anchor = None;
+        self.ensure_selection_visible()?;
+        self.update_logs_scrollbar_state();
+        self.update_autoscroll_state();
+        Ok(())
+    }
+
+    /// Saves the filter box, hidden levels and detail level under `name`, replacing a preset
+    /// of the same name
+    fn save_preset(&mut self, name: String) {
+        let preset = Preset {
+            name: name.clone(),
+            filter: self.filter_input.clone(),
+            hidden_levels: theme::LEVELS
+                .iter()
+                .filter(|level| self.hidden_levels.contains(*level))
+                .map(|level| level.to_string())
+                .collect(),
+            detail_level: self.detail_level,
+        };
+        let slot = self.presets.upsert(preset);
+        if self.save_presets() {
+            self.toast(format!("Saved preset '{}' in slot {}", name, slot + 1));
+        }
+    }
+
+    /// Writes the presets out, toasting why if that fails
+    fn save_presets(&mut self) -> bool {
+        let result = match &self.presets_path {
+            Some(path) => self.presets.save(path),
+            None => Ok(()),
+        };
+        if let Err(e) = &result {
+            self.toast(format!("{:#}", e));
+        }
+        result.is_ok()
+    }
+
+    fn apply_preset(&mut self, index: usize) {
+        let Some(preset) = self.presets.presets.get(index).cloned() else {
+            self.toast(format!("No preset in slot {}", index + 1));
+            return;
+        };
+        self.filter_input = preset.filter;
+        self.hidden_levels = theme::LEVELS
+            .into_iter()
+            .filter(|level| preset.hidden_levels.iter().any(|hidden| hidden == level))
+            .collect();
+        self.detail_level = preset.detail_level.min(MAX_DETAIL_LEVEL);
+        self.apply_filter(false);
+        self.toast(format!("Preset '{}'", preset.name));
+    }
+
+    fn select_picker_row(&mut self) {
+        self.picker_mode = false;
+        self.field_filter = match self.picker_selected {
+            0 => None,
+            row => self
+                .picker_values()
+                .into_iter()
+                .nth(row - 1)
+                .map(|(value, _)| FieldFilter {
+                    field: self.picker_field,
+                    value,
+                }),
+        };
+        self.apply_filter(false);
+    }
+
+    fn exit_filter_mode(&mut self) {
+        self.filter_mode = false;
+        // Revert to the view from before the filter input was opened
+        *self.target_filter_mut() = std::mem::take(&mut self.filter_before_edit);
+        self.apply_target_filter(false);
+    }
+
+    /// The filter text the prompt edits
+    fn target_filter_mut(&mut self) -> &mut String {
+        match self.filter_target {
+            FilterTarget::Logs => &mut self.filter_input,
+            FilterTarget::Debug => &mut self.debug_filter,
+        }
+    }
+
+    /// Brings the panel the prompt edits up to date with its filter; `narrowing` as for
+    /// `apply_filter`. DEBUG LOGS filters as it draws, so it only goes back to the top.
+    fn apply_target_filter(&mut self, narrowing: bool) {
+        match self.filter_target {
+            FilterTarget::Logs => self.apply_filter(narrowing),
+            FilterTarget::Debug => self.debug_block.set_scroll_position(0),
+        }
+    }
+
+    fn update_logs_scrollbar_state(&mut self) {
+        let total = self.displaying_logs.items.len();
+
+        {
+            // Clamp position to valid range
+            let max_top = total.saturating_sub(1);
+            let pos = self.logs_block.get_scroll_position().min(max_top);
+            self.logs_block.set_scroll_position(pos);
+
+            self.logs_block.set_lines_count(total);
+            self.logs_block.update_scrollbar_state(total, Some(pos));
+        }
+    }
+
+    fn render_header(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        // One `[E]` cell per level on the right; clicking a cell shows/hides that level
+        const LEGEND_CELL_WIDTH: u16 = 3;
+        let [title_area, legend_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(LEGEND_CELL_WIDTH * theme::LEVELS.len() as u16 + 1),
+        ])
+        .areas(area);
+
+        if let Some(event) = self.event
+            && event.kind == MouseEventKind::Up(crossterm::event::MouseButton::Left)
+            && legend_area.contains(ratatui::layout::Position::new(event.column, event.row))
+            && let Some(level) =
+                theme::LEVELS.get(((event.column - legend_area.x) / LEGEND_CELL_WIDTH) as usize)
+        {
+            if !self.hidden_levels.remove(level) {
+                self.hidden_levels.insert(level);
+            }
+            log::debug!("Toggled {} logs from the header legend", level);
+            self.apply_filter(false);
+        }
+
+        let legend: Vec<Span> = theme::LEVELS
+            .iter()
+            .map(|level| {
+                let cell = format!("[{}]", &level[..1]);
+                if self.hidden_levels.contains(level) {
+                    cell.fg(theme::HIDDEN_LEVEL_FG_COLOR)
+                } else {
+                    Span::styled(cell, theme::level_style(level).bold())
+                }
+            })
+            .collect();
+        Line::from(legend).render(legend_area, buf);
+
+        let autoscroll_status = match (self.autoscroll, self.autoscroll_mode) {
+            (true, _) => "ON",
+            (false, AutoscrollMode::Auto) => "paused — press g or a to resume",
+            (false, AutoscrollMode::Manual) => "OFF — press a to resume",
+        };
+        let mut title = self.header_title.clone();
+        if self.header_shows_file && self.log_file_path.exists() {
+            title.push_str(&format!(
+                " | {}",
+                self.log_file_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            ));
+        }
+        title.push_str(&format!(" | Autoscroll {}", autoscroll_status));
+
+        if !self.mouse_capture {
+            title.push_str(" | Mouse off");
+        }
+
+        if let Some((read, total)) = self.load_progress {
+            title.push_str(&format!(" | Loading {}%", read * 100 / total.max(1)));
+        }
+
+        if let Some(last_update) = self.last_update_instant {
+            let elapsed = last_update.elapsed();
+            let wall_time =
+                chrono::Local::now() - chrono::Duration::from_std(elapsed).unwrap_or_default();
+            title.push_str(&format!(" | Last update {}", wall_time.format("%H:%M:%S")));
+
+            // Spin while the file keeps growing, i.e. it changed within the last couple of polls
+            if elapsed < Duration::from_secs(2) {
+                const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+                let frame = self.animation_frame(Duration::from_millis(100));
+                title.push(' ');
+                title.push(SPINNER[frame % SPINNER.len()]);
+            }
+        }
+
+        Paragraph::new(title)
+            .bold()
+            .centered()
+            .render(title_area, buf);
+        Ok(())
+    }
+
+    /// How much of the file has been parsed, e.g. `1.2 MiB / 3.4 MiB` during a big initial
+    /// load and just the length once everything is read
+    fn file_size_readout(&self) -> Option<String> {
+        let total = match self.load_progress {
+            Some((_, total)) => total,
+            None => self.prev_meta.as_ref()?.len,
+        };
+        Some(if self.last_len < total {
+            format!("{} / {}", human_size(self.last_len), human_size(total))
+        } else {
+            human_size(total)
+        })
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let area = match self.file_size_readout() {
+            Some(readout) => {
+                let [help_area, size_area] = Layout::horizontal([
+                    Constraint::Fill(1),
+                    Constraint::Length(readout.width() as u16 + 2),
+                ])
+                .areas(area);
+                Paragraph::new(format!(" {} ", readout))
+                    .dim()
+                    .render(size_area, buf);
+                help_area
+            }
+            None => area,
+        };
+        let help_text = if self.filter_mode && self.filter_target == FilterTarget::Debug {
+            format!(
+                "Filter DEBUG LOGS: {} (text, ignoring case, Enter to keep, Esc to cancel)",
+                self.debug_filter
+            )
+        } else if self.filter_mode {
+            match &self.filter_error {
+                Some(e) => format!("Filter: {} ({})", self.filter_input, e),
+                None => format!(
+                    "Filter: {} (field:value for level/origin/tag/content, AND/OR/NOT, Enter to keep, Esc to cancel)",
+                    self.filter_input
+                ),
+            }
+        } else if self.palette_mode {
+            format!(
+                ":{} (↑↓ to choose, Enter to run, Esc to cancel)",
+                self.palette_input
+            )
+        } else if let Some(name) = &self.preset_name_input {
+            format!("Save preset as: {} (Enter to save, Esc to cancel)", name)
+        } else if let Some(pattern) = &self.grep_input {
+            format!(
+                "Grep file: {} (raw text, ignoring case, Enter to search, Esc to cancel)",
+                pattern
+            )
+        } else if self.file_grep.is_some() {
+            "↑↓: choose | Enter: jump to nearest log | Esc: close".to_string()
+        } else if self.help.is_some() {
+            "↑↓: scroll | Esc: close".to_string()
+        } else if self.yank_menu.is_some() {
+            "↑↓/1-5: choose | Enter: yank, and make it what y copies | Esc: cancel".to_string()
+        } else if self.preset_picker.is_some() {
+            "↑↓: choose | Enter: apply | d: delete | Esc: cancel".to_string()
+        } else if self.picker_mode {
+            "↑↓: choose | Tab: origin/tag | Enter: filter by value | Esc: cancel".to_string()
+        } else if let Some((message, expires)) = &self.toast
+            && Instant::now() < *expires
+        {
+            Paragraph::new(Span::styled(format!(" {} ", message), theme::TOAST_STYLE))
+                .centered()
+                .render(area, buf);
+            return Ok(());
+        } else {
+            // The rest are listed by `?`
+            "jk↑↓: nav | a: autoscroll | /: filter | y: yank | Tab: focus | :: commands | ?: keys | q: quit"
+                .to_string()
+        };
+        Paragraph::new(help_text).centered().render(area, buf);
+        Ok(())
+    }
+
+    fn render_logs(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        // Store the area for selection visibility calculations
+        self.last_logs_area = Some(area);
+
+        // Create a horizontal layout: main content area + scrollbar area
+        let [content_area, scrollbar_area] = Layout::horizontal([
+            Constraint::Fill(1),   // Main content takes most space
+            Constraint::Length(1), // Scrollbar is 1 character wide
+        ])
+        .margin(0)
+        .areas(area);
+
+        let is_log_focused = self.is_log_block_focused().unwrap_or(false);
+
+        // Get and update the LOGS block (title, mouse focus)
         let title = if self.log_file_path.exists() {
             format!(
-                "LOGS | Detail Level: {} | {}",
+                "LOGS | Detail Level: {} | {}{}",
                 self.detail_level,
                 self.log_file_path
                     .file_name()
                     .unwrap_or_default()
-                    .to_string_lossy()
+                    .to_string_lossy(),
+                if self.is_archive { " (archived)" } else { "" }
             )
         } else {
             format!(
@@ -493,17 +1645,51 @@ impl App {
                 self.detail_level
             )
         };
+        let title = if self.group_by_tag {
+            format!("{} | By Tag", title)
+        } else {
+            title
+        };
+        // Wrapping only changes the LOGS list when previews span several lines
+        let title = if self.preview_options.lines.unwrap_or(1) > 1 {
+            format!("{} | {}", title, wrap_label(self.wrap))
+        } else {
+            title
+        };
+        let title = match &self.field_filter {
+            Some(field_filter) => format!(
+                "{} | {}: {}",
+                title,
+                field_filter.field.name(),
+                field_filter.value
+            ),
+            None => title,
+        };
+        let title = if self.filter_suspended && self.has_filter() {
+            format!("{} | Filter Off", title)
+        } else {
+            title
+        };
+        // Newest logs are at the top, so that's where the unread ones are
+        let title = if self.show_unread && self.unread > 0 {
+            format!("{} | ↑ {} new", title, self.unread)
+        } else {
+            title
+        };
         self.logs_block.update_title(title);
         let logs_block_id = self.logs_block.id();
 
-        let (should_focus, clicked_row) = if let Some(event) = self.event {
-            let was_clicked =
+        let (should_focus, clicked_row, extend_selection) = if let Some(event) = self.event {
+            let should_focus =
                 self.logs_block
-                    .handle_mouse_event(&event, content_area, self.event.as_ref());
+                    .handle_mouse_event(&event, content_area, is_log_focused);
             let is_left_click = event.kind
                 == crossterm::event::MouseEventKind::Up(crossterm::event::MouseButton::Left);
 
-            let inner_area = self.logs_block.build(false).inner(content_area);
+            // Hit-test against the block as it was drawn, focused borders included
+            let inner_area = self
+                .logs_block
+                .get_content_rect(content_area, is_log_focused);
             let is_within_bounds =
                 inner_area.contains(ratatui::layout::Position::new(event.column, event.row));
             let click_row = if is_left_click && is_within_bounds {
@@ -511,9 +1697,12 @@ impl App {
             } else {
                 None
             };
-            (was_clicked, click_row)
+            let is_shift = event
+                .modifiers
+                .contains(crossterm::event::KeyModifiers::SHIFT);
+            (should_focus, click_row, is_shift)
         } else {
-            (false, None)
+            (false, None, false)
         };
 
         if should_focus {
@@ -523,15 +1712,25 @@ impl App {
         // Use the displaying_logs which contains either filtered or all logs
         let items_to_render = &self.displaying_logs.items;
         let selected_index = self.displaying_logs.state.selected();
+        let selected_range = self.selected_range();
         let total_lines = items_to_render.len();
 
-        // Compute inner content rect and visible height
+        // Compute inner content rect; the rows left for logs follow from the clamped scroll
         let inner_area = self
             .logs_block
             .get_content_rect(content_area, is_log_focused);
-        let visible_height = inner_area.height as usize;
         let content_width = inner_area.width as usize;
 
+        // Unselected rows are indented by the indicator's width so text doesn't shift
+        let indicator_blank = " ".repeat(self.selection_indicator.width());
+
+        // Wide enough for the largest 1-based index into raw_logs
+        let gutter_width = if self.show_line_numbers {
+            self.raw_logs.len().max(1).to_string().len()
+        } else {
+            0
+        };
+
         // Clamp scroll position
         let logs_block = &mut self.logs_block;
         let mut scroll_position = logs_block.get_scroll_position();
@@ -544,64 +1743,220 @@ impl App {
             logs_block.set_scroll_position(scroll_position);
         }
 
+        // Rows taken by the live edge and older-logs markers don't show logs
+        let (top_marker, older_count) = self.logs_markers(inner_area.height as usize);
+        let top_marker_rows = usize::from(top_marker.is_some());
+        let visible_height = self.logs_list_height(inner_area.height as usize);
+
         // Handle click selection (convert row to absolute index in reversed order)
         let mut selection_changed = false;
         if let Some(click_row) = clicked_row {
-            let relative_row = click_row.saturating_sub(inner_area.y);
-            let exact_item_number = scroll_position.saturating_add(relative_row as usize);
-            if exact_item_number < total_lines {
+            let relative_row = (click_row.saturating_sub(inner_area.y) as usize)
+                .checked_sub(top_marker_rows)
+                .filter(|row| *row < visible_height);
+            let exact_item_number = relative_row.map(|row| scroll_position.saturating_add(row));
+            // Clicks on a marker, or beyond the end of available lines, are ignored
+            if let Some(exact_item_number) = exact_item_number
+                && exact_item_number < total_lines
+            {
+                if extend_selection {
+                    // The current selection becomes the fixed end of the range
+                    if self.selection_anchor.is_none() {
+                        self.selection_anchor = self.selected_log_uuid;
+                    }
+                } else {
+                    self.selection_anchor = None;
+                }
                 self.displaying_logs.state.select(Some(exact_item_number));
                 selection_changed = true;
             }
-            // Click beyond the end of available lines is ignored
         }
 
         // Build only the visible slice of lines
         let end = (scroll_position + visible_height).min(total_lines);
         let start = scroll_position.min(end);
 
-        let mut content_lines = Vec::with_capacity(end.saturating_sub(start));
+        // Unwrapped, multi-line previews are cut down to their first line
+        let preview_options = if self.wrap {
+            self.preview_options
+        } else {
+            PreviewOptions {
+                lines: None,
+                ..self.preview_options
+            }
+        };
+
+        // Align the bracketed fields across the rows on screen
+        let column_widths = ColumnWidths::of(
+            (start..end)
+                .map(|i| &items_to_render[App::to_underlying_index(total_lines, i)])
+                .filter(|item| !self.group_headers.contains_key(&item.id)),
+        );
+
+        // Ages for fading are measured from the newest timestamped log
+        let newest_time = self
+            .age_fade
+            .then(|| items_to_render.iter().rev().find_map(|item| item.timestamp))
+            .flatten();
+
+        let mut content_lines = Vec::with_capacity(end.saturating_sub(start) + 2);
+        match top_marker {
+            Some(TopMarker::Live) => content_lines.push(marker_line("live")),
+            Some(TopMarker::StickyTime) => {
+                // Untimed rows (special events, headers) go by the log before them
+                let top = App::to_underlying_index(total_lines, start);
+                let minute = items_to_render[..=top.min(total_lines - 1)]
+                    .iter()
+                    .rev()
+                    .find_map(|item| item.timestamp)
+                    .map_or("no time".to_string(), |time| {
+                        time.format("%Y-%m-%d %H:%M").to_string()
+                    });
+                content_lines.push(marker_line(&minute));
+            }
+            None => {}
+        }
         for i in start..end {
             // Map the visual index (0 = newest/top) to underlying item index
             let item_idx = total_lines.saturating_sub(1).saturating_sub(i);
             let log_item = &items_to_render[item_idx];
+            let group_header = self.group_headers.get(&log_item.id);
 
-            let detail_text = log_item.get_preview_text(self.detail_level);
-            let level_style = match log_item.level.as_str() {
-                "ERROR" => theme::ERROR_STYLE,
-                "WARN" => theme::WARN_STYLE,
-                "INFO" => theme::INFO_STYLE,
-                "DEBUG" => theme::DEBUG_STYLE,
-                _ => Style::default().fg(theme::TEXT_FG_COLOR),
+            let detail_text = match group_header {
+                Some((tag, count)) => format!(
+                    "{} {} ({})",
+                    if self.collapsed_groups.contains(tag) {
+                        "▸"
+                    } else {
+                        "▾"
+                    },
+                    if tag.is_empty() { "(no tag)" } else { tag },
+                    count
+                ),
+                None => {
+                    let preview = log_item.get_aligned_preview_text(
+                        self.detail_level,
+                        &preview_options,
+                        &column_widths,
+                    );
+                    let preview = if self.show_sequence {
+                        format!("#{} {}", log_item.seq, preview)
+                    } else {
+                        preview
+                    };
+                    // Members are indented under their section's header
+                    if self.group_by_tag {
+                        format!("  {}", preview)
+                    } else {
+                        preview
+                    }
+                }
+            };
+            let level_style = match group_header {
+                Some(_) => theme::GROUP_HEADER_STYLE,
+                // A tag's own level beats the log's
+                None => match self.tag_levels.get(&log_item.tag.to_lowercase()) {
+                    Some(level) => theme::level_style(level),
+                    None => theme::level_style(&log_item.level),
+                },
+            };
+            let level_style = match (newest_time, log_item.timestamp) {
+                (Some(newest), Some(time)) => {
+                    theme::age_faded(level_style, (newest - time).num_seconds())
+                }
+                _ => level_style,
             };
 
             // Selection highlighting uses the same (reversed) indices (selected_index compares to i)
             let is_selected = selected_index == Some(i);
-            let display_text = if is_selected {
-                format!(">{}", detail_text)
+            let is_in_range = selected_range.is_some_and(|(lo, hi)| lo <= i && i <= hi);
+            let indicator = if is_selected {
+                self.selection_indicator.as_str()
+            } else {
+                indicator_blank.as_str()
+            };
+            let display_text = if self.show_line_numbers && group_header.is_none() {
+                format!(
+                    "{}{:>width$} {}",
+                    indicator,
+                    log_item.index + 1,
+                    detail_text,
+                    width = gutter_width
+                )
+            } else if self.show_line_numbers {
+                // Headers have no position in the file; keep them aligned with the members
+                format!(
+                    "{}{:>width$} {}",
+                    indicator,
+                    "",
+                    detail_text,
+                    width = gutter_width
+                )
             } else {
-                format!(" {}", detail_text)
+                format!("{}{}", indicator, detail_text)
             };
 
+            // Where the row repeats the second of the row above it, its bracketed time
+            let mut repeated_time = None;
+            if self.repeated_time != RepeatedTime::Show
+                && group_header.is_none()
+                && self.detail_level >= 1
+                && i > start
+                && items_to_render[App::to_underlying_index(total_lines, i - 1)]
+                    .same_second(log_item)
+            {
+                let bracketed = format!("[{}]", log_item.time);
+                let preview_start = display_text.len() - detail_text.len();
+                repeated_time = display_text[preview_start..]
+                    .find(&bracketed)
+                    .map(|at| preview_start + at..preview_start + at + bracketed.len());
+            }
+            let mut display_text = display_text;
+            if let Some(range) = &repeated_time
+                && self.repeated_time == RepeatedTime::Blank
+            {
+                let blank = " ".repeat(display_text[range.clone()].width());
+                display_text.replace_range(range.clone(), &blank);
+                repeated_time = None;
+            }
+
             let final_style = if is_selected {
-                level_style.patch(theme::SELECTED_STYLE)
+                level_style.patch(self.selection_style)
+            } else if is_in_range {
+                level_style.patch(theme::RANGE_STYLE)
             } else {
                 level_style
             };
 
             // Pad selected lines to full width for a clean highlight bar
-            let padded_text = if is_selected {
-                format!("{:<width$}", display_text, width = content_width)
+            let padded_text = if is_selected || is_in_range {
+                pad_to_width(display_text, content_width)
             } else {
                 display_text
             };
 
-            content_lines.push(Line::styled(padded_text, final_style));
+            content_lines.push(match repeated_time {
+                Some(range) => Line::from(vec![
+                    Span::raw(padded_text[..range.start].to_string()),
+                    Span::styled(
+                        padded_text[range.clone()].to_string(),
+                        theme::REPEATED_TIME_STYLE,
+                    ),
+                    Span::raw(padded_text[range.end..].to_string()),
+                ])
+                .style(final_style),
+                None => Line::styled(padded_text, final_style),
+            });
+        }
+
+        if older_count > 0 {
+            content_lines.push(marker_line(&format!("{} older", older_count)));
         }
 
         // Update scrollbar and line counts using TOTAL lines (not just the visible window)
         let logs_block = &mut self.logs_block;
         logs_block.set_lines_count(total_lines);
+        logs_block.set_viewport_height(visible_height);
         logs_block.update_scrollbar_state(total_lines, Some(scroll_position));
 
         // Build the block after mutable ops
@@ -615,7 +1970,7 @@ impl App {
             .render(content_area, buf);
 
         // Render the scrollbar using AppBlock's state
-        let scrollbar = AppBlock::create_scrollbar(is_log_focused);
+        let scrollbar = self.logs_block.create_scrollbar(is_log_focused);
         let logs_block = &mut self.logs_block;
         StatefulWidget::render(
             scrollbar,
@@ -630,6 +1985,7 @@ impl App {
         // Update UUID tracking if selection changed
         if selection_changed {
             self.update_selected_uuid();
+            self.arm_copy_on_select();
         }
 
         Ok(())
@@ -643,7 +1999,7 @@ impl App {
         // Handle click and set focus
         let should_focus = if let Some(event) = self.event {
             self.details_block
-                .handle_mouse_event(&event, area, self.event.as_ref())
+                .handle_mouse_event(&event, area, is_focused)
         } else {
             false
         };
@@ -660,33 +2016,214 @@ impl App {
         .margin(0)
         .areas(area);
 
-        // Use the displaying_logs which contains either filtered or all logs
-        let (items, state) = (&self.displaying_logs.items, &self.displaying_logs.state);
+        self.details_block
+            .update_title(format!("LOG DETAILS | {}", wrap_label(self.wrap)));
 
-        let content = if let Some(i) = state.selected() {
-            // Access items in reverse order to match the LOGS panel display order
-            let reversed_index = items.len().saturating_sub(1).saturating_sub(i);
-            let item = &items[reversed_index];
+        // Use the displaying_logs which contains either filtered or all logs
+        let items = &self.displaying_logs.items;
+        let selected_index = self.selected_log_index();
+        let selected_header = self
+            .displaying_logs
+            .state
+            .selected()
+            .and_then(|i| items.get(App::to_underlying_index(items.len(), i)))
+            .and_then(|item| self.group_headers.get(&item.id));
+
+        // Set when `content` only holds the rows in view: wrapped lines left out above it,
+        // and the line count of the whole item
+        let mut skipped_lines = 0;
+        let mut total_lines = None;
+        let mut content = if let Some(index) = selected_index {
+            let item = &items[index];
 
             // Check if the selected log item has changed and reset scroll position if needed
             if self.prev_selected_log_id != Some(item.id) {
+                self.diff_base_id = self.prev_selected_log_id;
                 self.prev_selected_log_id = Some(item.id);
                 self.details_block.set_scroll_position(0);
+                self.details_block.set_h_scroll_position(0);
             }
 
-            let mut content_lines = vec![
-                Line::from(vec!["Time:   ".bold(), item.time.clone().into()]),
-                Line::from(vec!["Level:  ".bold(), item.level.clone().into()]),
-                Line::from(vec!["Origin: ".bold(), item.origin.clone().into()]),
-                Line::from(vec!["Tag:    ".bold(), item.tag.clone().into()]),
-                Line::from("Content:".bold()),
-            ];
-            // Get the actual content rect accounting for borders
-            let content_rect = self
-                .details_block
-                .get_content_rect(content_area, is_focused);
-            content_lines.extend(wrap_content_to_lines(&item.content, content_rect.width));
-            content_lines
+            if self.details_hex {
+                let bytes = item.raw_content.as_bytes();
+                let mut content_lines =
+                    vec![Line::from(format!("Raw bytes ({}):", bytes.len()).bold())];
+                content_lines.extend(hex_dump_lines(bytes));
+                content_lines
+            } else if self.details_inspect {
+                let mut content_lines = vec![Line::from("Parse steps:".bold())];
+                for stage in self.parser.inspect(item) {
+                    content_lines.push(Line::from(format!("{}:", stage.name).bold()));
+                    content_lines.extend(
+                        stage
+                            .output
+                            .lines()
+                            .map(|line| Line::from(format!("  {}", line))),
+                    );
+                }
+                content_lines
+            } else {
+                // Get the actual content rect accounting for borders
+                let content_rect = self
+                    .details_block
+                    .get_content_rect(content_area, is_focused);
+                let diff_base = self
+                    .diff_base_id
+                    .filter(|_| self.details_diff)
+                    .and_then(|id| self.raw_logs.iter().find(|base| base.id == id));
+
+                if let Some(base) = diff_base {
+                    let field_line = |name: &'static str, old: &str, new: &str| {
+                        if old == new {
+                            Line::from(vec![name.bold(), new.to_string().into()])
+                        } else {
+                            Line::from(vec![
+                                name.bold(),
+                                Span::styled(new.to_string(), theme::DIFF_CHANGED_STYLE),
+                                format!(" (was {})", old).dim(),
+                            ])
+                        }
+                    };
+                    let mut content_lines = vec![
+                        field_line("Time:   ", &base.time, &item.time),
+                        field_line("Level:  ", &base.level, &item.level),
+                        field_line("Origin: ", &base.origin, &item.origin),
+                        field_line("Tag:    ", &base.tag, &item.tag),
+                        Line::from("Content (diff with previous selection):".bold()),
+                    ];
+                    for (op, line) in diff_lines(&base.content, &item.content) {
+                        let (prefix, style) = match op {
+                            DiffOp::Same => ("  ", Style::default()),
+                            DiffOp::Added => ("+ ", theme::DIFF_ADDED_STYLE),
+                            DiffOp::Removed => ("- ", theme::DIFF_REMOVED_STYLE),
+                        };
+                        let text = format!("{}{}", prefix, line);
+                        if self.wrap {
+                            content_lines.extend(
+                                wrap_content_to_lines(&text, content_rect.width)
+                                    .into_iter()
+                                    .map(|wrapped| Line::from(wrapped.to_string()).style(style)),
+                            );
+                        } else {
+                            content_lines.push(Line::from(text).style(style));
+                        }
+                    }
+                    content_lines
+                } else {
+                    let mut content_lines = vec![
+                        Line::from(vec!["Time:   ".bold(), item.time.clone().into()]),
+                        Line::from(vec!["Level:  ".bold(), item.level.clone().into()]),
+                        Line::from(vec!["Origin: ".bold(), item.origin.clone().into()]),
+                        Line::from(vec!["Tag:    ".bold(), item.tag.clone().into()]),
+                        Line::from("Content:".bold()),
+                    ];
+                    if self
+                        .details_json_cache
+                        .as_ref()
+                        .is_none_or(|(id, _)| *id != item.id)
+                    {
+                        let pretty = self
+                            .details_json
+                            .then(|| json::pretty_print(&item.content))
+                            .flatten();
+                        self.details_json_cache = Some((item.id, pretty));
+                    }
+                    let pretty = self
+                        .details_json_cache
+                        .as_ref()
+                        .and_then(|(_, pretty)| pretty.as_deref());
+                    let text = pretty.unwrap_or(&item.content);
+                    if self
+                        .details_highlight
+                        .as_ref()
+                        .is_none_or(|(input, _)| *input != self.filter_input)
+                    {
+                        self.details_highlight = Some((
+                            self.filter_input.clone(),
+                            highlight_regex(&self.filter_input),
+                        ));
+                    }
+                    // Byte ranges of `text` the filter matches, each line patched where it
+                    // overlaps one so the matches stand out on every item selected
+                    let matches: Vec<Range<usize>> = self
+                        .details_highlight
+                        .as_ref()
+                        .and_then(|(_, re)| re.as_ref())
+                        .map_or_else(Vec::new, |re| {
+                            re.find_iter(text).map(|m| m.range()).collect()
+                        });
+                    let make_line = |line, start: usize| {
+                        let line = match pretty {
+                            Some(_) => json_line(line),
+                            None => Line::from(line),
+                        };
+                        highlight_matches(line, start, &matches, theme::MATCH_STYLE)
+                    };
+
+                    if self.wrap {
+                        // Only the rows in view become `Line`s; the rest are just counted
+                        // for the scrollbar. Re-wrapped only when the selection, the panel
+                        // width or the text itself changed.
+                        let hash = text_hash(text);
+                        let cache = match &mut self.details_wrap_cache {
+                            Some(cache)
+                                if cache.id == item.id
+                                    && cache.width == content_rect.width
+                                    && cache.text_hash == hash =>
+                            {
+                                cache
+                            }
+                            cache => {
+                                let (ranges, hidden) = wrap_ranges(text, content_rect.width);
+                                cache.insert(WrapCache {
+                                    id: item.id,
+                                    width: content_rect.width,
+                                    text_hash: hash,
+                                    ranges,
+                                    hidden,
+                                })
+                            }
+                        };
+                        let (ranges, hidden) = (&*cache.ranges, cache.hidden);
+                        let header_len = content_lines.len();
+                        let first = self
+                            .details_block
+                            .get_scroll_position()
+                            .saturating_sub(header_len)
+                            .min(ranges.len());
+                        let last = (first + content_rect.height as usize).min(ranges.len());
+                        content_lines.extend(
+                            ranges[first..last]
+                                .iter()
+                                .map(|range| make_line(&text[range.clone()], range.start)),
+                        );
+                        if hidden > 0 && last == ranges.len() {
+                            content_lines.push(truncated_line(hidden));
+                        }
+                        skipped_lines = first;
+                        total_lines = Some(header_len + ranges.len() + usize::from(hidden > 0));
+                    } else {
+                        let mut start = 0;
+                        content_lines.extend(text.split('\n').map(|line| {
+                            let line_start = start;
+                            start += line.len() + 1;
+                            make_line(line, line_start)
+                        }));
+                    }
+                    content_lines
+                }
+            }
+        } else if let Some((tag, count)) = selected_header {
+            // Leave the previous log tracked, so diffing goes on past the header
+            vec![Line::from(
+                format!(
+                    "Section of {} log{} tagged [{}]. Space collapses or expands it.",
+                    count,
+                    if *count == 1 { "" } else { "s" },
+                    tag
+                )
+                .italic(),
+            )]
         } else {
             // No log item selected - clear the previous selection tracking
             if self.prev_selected_log_id.is_some() {
@@ -697,25 +2234,61 @@ impl App {
             vec![Line::from("Select a log item to see details...".italic())]
         };
 
+        // The field cursor for `y`: the header lines come first, one per DETAIL_FIELDS
+        if is_focused && !self.details_hex && !self.details_inspect && selected_index.is_some() {
+            for (i, line) in content.iter_mut().take(DETAIL_FIELDS.len()).enumerate() {
+                let marker = if i == self.details_field {
+                    "▸ "
+                } else {
+                    "  "
+                };
+                line.spans.insert(0, marker.bold());
+            }
+        }
+
         // The content vector already contains properly wrapped lines
-        let lines_count = content.len();
+        let lines_count = total_lines.unwrap_or(content.len());
 
         // Update the details block with lines count and scrollbar state
         self.details_block.set_lines_count(lines_count);
+        self.details_block.set_viewport_height(
+            self.details_block
+                .get_content_rect(content_area, is_focused)
+                .height as usize,
+        );
         let scroll_position = self.details_block.get_scroll_position();
         self.details_block
             .update_scrollbar_state(lines_count, Some(scroll_position));
 
+        // Clamp horizontal scrolling so the widest line's end stays in view. Hex dump and parse
+        // step lines are never wrapped, so they can always be panned.
+        let h_scroll_position = if self.wrap && !self.details_hex && !self.details_inspect {
+            0
+        } else {
+            let content_width = content.iter().map(Line::width).max().unwrap_or(0);
+            let view_width = self
+                .details_block
+                .get_content_rect(content_area, is_focused)
+                .width as usize;
+            let max_h_scroll = content_width.saturating_sub(view_width);
+            self.details_block.set_content_width(content_width);
+            self.details_block.get_h_scroll_position().min(max_h_scroll)
+        };
+        self.details_block.set_h_scroll_position(h_scroll_position);
+
         // Build the block after mutable operations
         let block = self.details_block.build(is_focused);
 
         Paragraph::new(content)
             .block(block)
             .fg(theme::TEXT_FG_COLOR)
-            .scroll((scroll_position as u16, 0))
+            .scroll((
+                (scroll_position - skipped_lines) as u16,
+                h_scroll_position as u16,
+            ))
             .render(content_area, buf);
 
-        let scrollbar = AppBlock::create_scrollbar(is_focused);
+        let scrollbar = self.details_block.create_scrollbar(is_focused);
 
         // Use AppBlock's scrollbar state
         StatefulWidget::render(
@@ -735,7 +2308,7 @@ impl App {
         // Handle click and set focus
         let should_focus = if let Some(event) = self.event {
             self.debug_block
-                .handle_mouse_event(&event, area, self.event.as_ref())
+                .handle_mouse_event(&event, area, is_focused)
         } else {
             false
         };
@@ -752,28 +2325,44 @@ impl App {
         .margin(0)
         .areas(area);
 
+        self.debug_block
+            .update_title(if self.debug_filter.is_empty() {
+                "DEBUG LOGS".to_string()
+            } else {
+                format!("DEBUG LOGS | Filter: {}", self.debug_filter)
+            });
         // Build the block after getting focus info
         let _block = self.debug_block.build(is_focused);
 
+        let needle = self.debug_filter.to_lowercase();
         let debug_logs_lines = if let Ok(logs) = self.debug_logs.lock() {
             if logs.is_empty() {
                 vec![Line::from("No debug logs...".italic())]
             } else {
-                logs.iter()
+                let lines: Vec<Line> = logs
+                    .iter()
                     .rev() // Show most recent first
-                    .map(|log_entry| {
-                        let style = if log_entry.contains("ERROR") {
-                            theme::ERROR_STYLE
-                        } else if log_entry.contains("WARN") {
-                            theme::WARN_STYLE
-                        } else if log_entry.contains("DEBUG") {
-                            theme::DEBUG_STYLE
-                        } else {
-                            Style::default().fg(theme::TEXT_FG_COLOR)
+                    .map(|record| (record, record.format(self.debug_timestamps)))
+                    // The module that logged it counts, so `app` finds everything from app.rs
+                    .filter(|(record, log_entry)| {
+                        log_entry.to_lowercase().contains(&needle)
+                            || record.target.to_lowercase().contains(&needle)
+                    })
+                    .map(|(record, log_entry)| {
+                        let style = match record.level {
+                            log::Level::Error => theme::ERROR_STYLE,
+                            log::Level::Warn => theme::WARN_STYLE,
+                            log::Level::Debug => theme::DEBUG_STYLE,
+                            _ => Style::default().fg(theme::TEXT_FG_COLOR),
                         };
-                        Line::styled(log_entry.clone(), style)
+                        Line::styled(log_entry, style)
                     })
-                    .collect()
+                    .collect();
+                if lines.is_empty() {
+                    vec![Line::from("No matching debug logs...".italic())]
+                } else {
+                    lines
+                }
             }
         } else {
             vec![Line::from("Failed to read debug logs...".italic())]
@@ -784,6 +2373,11 @@ impl App {
 
         // Update the debug block with lines count and scrollbar state
         self.debug_block.set_lines_count(lines_count);
+        self.debug_block.set_viewport_height(
+            self.debug_block
+                .get_content_rect(content_area, is_focused)
+                .height as usize,
+        );
         if !is_focused {
             self.debug_block.set_scroll_position(0);
         }
@@ -800,7 +2394,7 @@ impl App {
             .scroll((scroll_position as u16, 0))
             .render(content_area, buf);
 
-        let scrollbar = AppBlock::create_scrollbar(is_focused);
+        let scrollbar = self.debug_block.create_scrollbar(is_focused);
 
         // Use AppBlock's scrollbar state
         StatefulWidget::render(
@@ -812,57 +2406,399 @@ impl App {
         Ok(())
     }
 
-    fn is_log_block_focused(&self) -> Result<bool> {
-        if let Some(focused_id) = self.focused_block_id {
-            Ok(focused_id == self.logs_block.id())
-        } else {
-            Ok(false)
-        }
-    }
-
-    fn is_debug_block_focused(&self) -> Result<bool> {
-        if let Some(focused_id) = self.focused_block_id {
-            Ok(focused_id == self.debug_block.id())
-        } else {
-            Ok(false)
-        }
-    }
+    fn render_command_palette(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let matches = action::matching_actions(&self.palette_input);
+
+        // Center a box tall enough for every palette action
+        let height = (Action::PALETTE.len() as u16 + 2).min(area.height);
+        let width = 60.min(area.width);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
 
-    fn is_details_block_focused(&self) -> Result<bool> {
-        if let Some(focused_id) = self.focused_block_id {
-            Ok(focused_id == self.details_block.id())
+        let lines: Vec<Line> = if matches.is_empty() {
+            vec![Line::from("No matching commands".italic())]
         } else {
-            Ok(false)
-        }
-    }
-
-    fn ensure_selection_visible(&mut self) -> Result<()> {
-        let selected_index = self.displaying_logs.state.selected();
+            matches
+                .iter()
+                .enumerate()
+                .map(|(i, action)| {
+                    let line = Line::from(vec![
+                        format!(" {:<22}", action.name()).bold(),
+                        action.description().into(),
+                    ]);
+                    if i == self.palette_selected {
+                        line.style(theme::SELECTED_STYLE)
+                    } else {
+                        line
+                    }
+                })
+                .collect()
+        };
 
-        if let (Some(selected_idx), Some(visible_area)) = (selected_index, self.last_logs_area) {
-            {
-                let current_scroll_pos = self.logs_block.get_scroll_position();
+        // Keep the highlighted command inside the popup on terminals too short for all of them
+        let visible_rows = height.saturating_sub(2) as usize;
+        let scroll = (self.palette_selected + 1).saturating_sub(visible_rows);
 
-                // Calculate visible range within the content area
-                let content_rect = self.logs_block.get_content_rect(visible_area, false);
-                let visible_height = content_rect.height as usize;
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from("COMMANDS").centered());
 
-                if visible_height == 0 {
-                    return Ok(());
-                }
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .fg(theme::TEXT_FG_COLOR)
+            .render(popup, buf);
+        Ok(())
+    }
 
-                // Use padding = 1 when there is room; otherwise fall back to 0
-                let pad = if visible_height > 2 { 1 } else { 0 };
+    /// Developer stats in the top-right corner of `area`. Memory counts the parsed logs and
+    /// their displayed copies, not the whole process.
+    fn render_hud(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let memory: usize = self
+            .raw_logs
+            .iter()
+            .chain(&self.displaying_logs.items)
+            .map(LogItem::heap_size)
+            .sum();
+        let frame = self.last_frame_time.map_or("-".to_string(), |time| {
+            format!("{:.1} ms", time.as_secs_f64() * 1000.0)
+        });
+        let lines = vec![
+            Line::from(format!(" parsed  {}", self.raw_logs.len())),
+            Line::from(format!(" shown   {}", self.displaying_logs.items.len())),
+            Line::from(format!(" memory  ~{}", human_size(memory as u64))),
+            Line::from(format!(" frame   {}", frame)),
+        ];
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 24.min(area.width);
+        let hud = Rect::new(area.right() - width, area.y, width, height);
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from("HUD").centered());
+
+        Clear.render(hud, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .style(theme::DEBUG_STYLE)
+            .render(hud, buf);
+        Ok(())
+    }
+
+    fn render_yank_menu(&self, area: Rect, buf: &mut Buffer, selected: usize) -> Result<()> {
+        let lines: Vec<Line> = YankFormat::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, format)| {
+                let default = if *format == self.yank_format {
+                    "  (y)"
+                } else {
+                    ""
+                };
+                let row = format!(" {} {}{}", i + 1, format.label(), default);
+                if i == selected {
+                    Line::from(row).style(theme::SELECTED_STYLE)
+                } else {
+                    Line::from(row)
+                }
+            })
+            .collect();
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 32.min(area.width);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from("YANK AS").centered());
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .fg(theme::TEXT_FG_COLOR)
+            .render(popup, buf);
+        Ok(())
+    }
+
+    fn render_help(&self, area: Rect, buf: &mut Buffer, selected: usize) -> Result<()> {
+        let lines: Vec<Line> = action::KEY_HELP
+            .iter()
+            .enumerate()
+            .map(|(i, (keys, description))| {
+                let line = Line::from(vec![format!(" {:<12}", keys).bold(), (*description).into()]);
+                if i == selected {
+                    line.style(theme::SELECTED_STYLE)
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 66.min(area.width);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let visible_rows = height.saturating_sub(2) as usize;
+        let scroll = (selected + 1).saturating_sub(visible_rows);
+
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from("KEYS").centered());
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .fg(theme::TEXT_FG_COLOR)
+            .render(popup, buf);
+        Ok(())
+    }
+
+    fn render_preset_picker(&self, area: Rect, buf: &mut Buffer, selected: usize) -> Result<()> {
+        let lines: Vec<Line> = self
+            .presets
+            .presets
+            .iter()
+            .enumerate()
+            .map(|(i, preset)| {
+                let row = format!(" {} {}  {}", i + 1, preset.name, preset.filter);
+                if i == selected {
+                    Line::from(row).style(theme::SELECTED_STYLE)
+                } else {
+                    Line::from(row)
+                }
+            })
+            .collect();
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 50.min(area.width);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let visible_rows = height.saturating_sub(2) as usize;
+        let scroll = (selected + 1).saturating_sub(visible_rows);
+
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from("PRESETS").centered());
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .fg(theme::TEXT_FG_COLOR)
+            .render(popup, buf);
+        Ok(())
+    }
+
+    fn render_file_grep(&self, area: Rect, buf: &mut Buffer, grep: &FileGrep) -> Result<()> {
+        let popup = Rect::new(
+            area.x + 2,
+            area.y + 1,
+            area.width.saturating_sub(4),
+            area.height.saturating_sub(2),
+        );
+        let capped = if grep.matches.len() == file_grep::MAX_MATCHES {
+            " (stopped there)"
+        } else {
+            ""
+        };
+        let block = Block::bordered().border_type(self.border_type).title(
+            Line::from(format!(
+                "FILE GREP | '{}' | {} lines{}",
+                grep.pattern,
+                grep.matches.len(),
+                capped
+            ))
+            .centered(),
+        );
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+
+        let [list_area, context_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(GREP_CONTEXT_LINES as u16 * 2 + 2),
+        ])
+        .areas(inner);
+
+        let lines: Vec<Line> = grep
+            .matches
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let row = format!(" {:>6}  {}", m.line_number, m.line);
+                if i == grep.selected {
+                    Line::from(row).style(theme::SELECTED_STYLE)
+                } else {
+                    Line::from(row)
+                }
+            })
+            .collect();
+        let scroll = (grep.selected + 1).saturating_sub(list_area.height as usize);
+        Paragraph::new(lines)
+            .scroll((scroll as u16, 0))
+            .fg(theme::TEXT_FG_COLOR)
+            .render(list_area, buf);
+
+        let selected = &grep.matches[grep.selected];
+        let context: Vec<Line> =
+            file_grep::surrounding_lines(&grep.bytes, selected.line_number, GREP_CONTEXT_LINES)
+                .into_iter()
+                .map(|(n, line)| {
+                    let row = format!(" {:>6}  {}", n, line);
+                    if n == selected.line_number {
+                        Line::from(row).bold()
+                    } else {
+                        Line::from(row)
+                    }
+                })
+                .collect();
+        Paragraph::new(context)
+            .block(
+                Block::new()
+                    .borders(Borders::TOP)
+                    .border_type(self.border_type)
+                    .title(format!(
+                        "Line {} at byte {}",
+                        selected.line_number, selected.offset
+                    )),
+            )
+            .fg(theme::TEXT_FG_COLOR)
+            .render(context_area, buf);
+        Ok(())
+    }
+
+    fn render_field_picker(&self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let values = self.picker_values();
+
+        let rows = std::iter::once(format!(" All ({})", self.raw_logs.len())).chain(
+            values
+                .iter()
+                .map(|(value, count)| format!(" {} ({})", value, count)),
+        );
+        let lines: Vec<Line> = rows
+            .enumerate()
+            .map(|(i, row)| {
+                if i == self.picker_selected {
+                    Line::from(row).style(theme::SELECTED_STYLE)
+                } else {
+                    Line::from(row)
+                }
+            })
+            .collect();
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 50.min(area.width);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        // Keep the highlighted row inside the popup when there are more values than rows
+        let visible_rows = height.saturating_sub(2) as usize;
+        let scroll = (self.picker_selected + 1).saturating_sub(visible_rows);
+
+        let title = match self.picker_field {
+            Field::Origin => "ORIGINS | Tab: tags",
+            _ => "TAGS | Tab: origins",
+        };
+        let block = Block::bordered()
+            .border_type(self.border_type)
+            .title(Line::from(title).centered());
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .fg(theme::TEXT_FG_COLOR)
+            .render(popup, buf);
+        Ok(())
+    }
+
+    fn is_log_block_focused(&self) -> Result<bool> {
+        if let Some(focused_id) = self.focused_block_id {
+            Ok(focused_id == self.logs_block.id())
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn is_debug_block_focused(&self) -> Result<bool> {
+        if let Some(focused_id) = self.focused_block_id {
+            Ok(focused_id == self.debug_block.id())
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn is_details_block_focused(&self) -> Result<bool> {
+        if let Some(focused_id) = self.focused_block_id {
+            Ok(focused_id == self.details_block.id())
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn ensure_selection_visible(&mut self) -> Result<()> {
+        let selected_index = self.displaying_logs.state.selected();
+
+        if let (Some(selected_idx), Some(visible_area)) = (selected_index, self.last_logs_area) {
+            {
+                let current_scroll_pos = self.logs_block.get_scroll_position();
+
+                // Calculate visible range within the content area
+                let content_rect = self
+                    .logs_block
+                    .get_content_rect(visible_area, self.is_log_block_focused()?);
+                let visible_height = self.logs_list_height(content_rect.height as usize);
+
+                if visible_height == 0 {
+                    return Ok(());
+                }
+
+                // Keep `scrolloff` rows of context, shrinking it in views too short to fit it
+                // on both sides of the selection
+                let pad = self.scrolloff.min(visible_height.saturating_sub(1) / 2);
 
                 let view_start = current_scroll_pos;
                 let view_end = current_scroll_pos + visible_height.saturating_sub(1);
 
+                if self.keep_selection_centered {
+                    // Clamp so a margin larger than half the view still leaves a middle row
+                    let margin = self.center_margin.min(visible_height.saturating_sub(1) / 2);
+                    if selected_idx < view_start.saturating_add(margin)
+                        || selected_idx > view_end.saturating_sub(margin)
+                    {
+                        self.center_selection();
+                    }
+                    return Ok(());
+                }
+
                 // Keep selected inside [view_start + pad, view_end - pad] when possible
                 let mut new_scroll_pos = if selected_idx < view_start.saturating_add(pad) {
-                    // Scroll up so selected appears at second line (if pad == 1)
+                    // Scroll up so `pad` rows show above the selection
                     selected_idx.saturating_sub(pad)
                 } else if selected_idx > view_end.saturating_sub(pad) {
-                    // Scroll down so selected is not the last line (keeps a 1-line bottom margin when possible)
+                    // Scroll down so `pad` rows show below the selection
                     selected_idx
                         .saturating_add(pad)
                         .saturating_add(1)
@@ -886,10 +2822,90 @@ impl App {
         Ok(())
     }
 
+    /// Marker rows of a LOGS view `height` rows tall: the row shown above the logs, if any,
+    /// and how many logs are below the view when an "N older" row is shown under it
+    fn logs_markers(&self, height: usize) -> (Option<TopMarker>, usize) {
+        let total = self.displaying_logs.items.len();
+        let scroll_position = self.logs_block.get_scroll_position();
+        let live = total > 0
+            && height > 1
+            && scroll_position == 0
+            && self.autoscroll
+            && self.load_progress.is_none();
+        let top = if live {
+            Some(TopMarker::Live)
+        } else if self.sticky_time && total > 0 && height > 2 {
+            Some(TopMarker::StickyTime)
+        } else {
+            None
+        };
+        let rows = height - usize::from(top.is_some());
+        let below = total.saturating_sub(scroll_position);
+        let older = if below > rows && rows > 1 {
+            below - (rows - 1)
+        } else {
+            0
+        };
+        (top, older)
+    }
+
+    /// Rows of a LOGS view `height` rows tall left for logs by the marker rows
+    fn logs_list_height(&self, height: usize) -> usize {
+        let (top, older) = self.logs_markers(height);
+        height - usize::from(top.is_some()) - usize::from(older > 0)
+    }
+
+    /// Scrolls the LOGS list so the selection sits in the middle of the view, as far as the
+    /// ends of the list allow
+    fn center_selection(&mut self) {
+        let (Some(selected_idx), Some(visible_area)) =
+            (self.displaying_logs.state.selected(), self.last_logs_area)
+        else {
+            return;
+        };
+        let is_focused = self.is_log_block_focused().unwrap_or(false);
+        let height = self
+            .logs_block
+            .get_content_rect(visible_area, is_focused)
+            .height as usize;
+        let visible_height = self.logs_list_height(height);
+        let total_items = self.displaying_logs.items.len();
+
+        // Scrolled to the end there's no "N older" row, so the whole height shows logs
+        let new_scroll_pos = selected_idx
+            .saturating_sub(visible_height / 2)
+            .min(total_items.saturating_sub(height));
+        self.logs_block.set_scroll_position(new_scroll_pos);
+        self.logs_block
+            .update_scrollbar_state(total_items, Some(new_scroll_pos));
+    }
+
+    /// Turns autoscroll back on from the newest log, like `g`
+    fn resume_autoscroll(&mut self) {
+        self.autoscroll = true;
+        self.autoscroll_held_off = false;
+        self.unread = 0;
+        self.displaying_logs.select_first();
+        self.update_selected_uuid();
+        self.selection_anchor = None;
+        self.logs_block.set_scroll_position(0);
+        self.update_logs_scrollbar_state();
+    }
+
     fn update_autoscroll_state(&mut self) {
         // Enable autoscroll when the view is at the topmost position (scroll position 0)
-        // Disable autoscroll when the view is not at the top
-        self.autoscroll = self.logs_block.get_scroll_position() == 0;
+        // Disable autoscroll when the view is not at the top. In manual mode only the
+        // toggle key changes it.
+        if self.autoscroll_mode == AutoscrollMode::Auto {
+            let at_top = self.logs_block.get_scroll_position() == 0;
+            if !at_top {
+                self.autoscroll_held_off = false;
+            }
+            self.autoscroll = at_top && !self.autoscroll_held_off;
+        }
+        if self.autoscroll {
+            self.unread = 0;
+        }
     }
 
     fn handle_log_item_scrolling(&mut self, move_next: bool, circular: bool) -> Result<()> {
@@ -911,10 +2927,13 @@ impl App {
 
         // Update the tracked UUID for the new selection
         self.update_selected_uuid();
+        self.selection_anchor = None;
+        self.arm_copy_on_select();
 
         // Ensure the newly selected item is visible
         self.ensure_selection_visible()?;
         self.update_logs_scrollbar_state();
+        self.update_autoscroll_state();
         Ok(())
     }
 
@@ -939,6 +2958,10 @@ impl App {
                 .update_scrollbar_state(lines_count, Some(new_position));
         }
 
+        // Decide right away rather than at the next render, so a delta processed before then
+        // can't snap the view back to the top
+        self.update_autoscroll_state();
+
         Ok(())
     }
 
@@ -969,6 +2992,25 @@ impl App {
         Ok(())
     }
 
+    fn handle_details_h_scrolling(&mut self, move_right: bool) -> Result<()> {
+        if self.wrap && !self.details_hex && !self.details_inspect {
+            return Ok(());
+        }
+
+        let current_position = self.details_block.get_h_scroll_position();
+        let new_position = if move_right {
+            current_position
+                .saturating_add(1)
+                .min(self.details_block.get_content_width())
+        } else {
+            current_position.saturating_sub(1)
+        };
+
+        // render_details tightens this to the visible width
+        self.details_block.set_h_scroll_position(new_position);
+        Ok(())
+    }
+
     fn handle_debug_logs_scrolling(&mut self, move_next: bool) -> Result<()> {
         let lines_count = self.debug_block.get_lines_count();
         if lines_count == 0 {
@@ -997,45 +3039,216 @@ impl App {
     }
 
     fn make_yank_content(&self, item: &LogItem) -> String {
-        format!(
-            "# Formatted Log\n\n## Time:\n\n{}\n\n## Level:\n\n{}\n\n## Origin:\n\n{}\n\n## Tag:\n\n{}\n\n## Content:\n\n{}\n\n# Raw Log\n\n{}",
-            item.time, item.level, item.origin, item.tag, item.content, item.raw_content
-        )
+        item.to_markdown()
     }
 
-    fn yank_current_log(&self) -> Result<()> {
-        // Use the displaying_logs which contains either filtered or all logs
-        let (items, state) = (&self.displaying_logs.items, &self.displaying_logs.state);
-
-        let Some(i) = state.selected() else {
-            log::debug!("No log item selected for yanking");
-            return Ok(());
-        };
+    fn make_range_yank_content(&self, items: &[&LogItem]) -> String {
+        items
+            .iter()
+            .map(|item| format!("[{}] {}", item.time, item.raw_content))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        // Access items in reverse order to match the LOGS panel display order
-        let reversed_index = items.len().saturating_sub(1).saturating_sub(i);
-        let item = &items[reversed_index];
+    /// Tab-separated table of the LOGS list in display order, with a header row
+    /// The displayed logs as a TSV table, and how many rows of logs it holds
+    fn make_tsv_yank_content(&self) -> (String, usize) {
+        let rows: Vec<String> = self
+            .displaying_logs
+            .items
+            .iter()
+            .rev()
+            .filter(|item| !self.group_headers.contains_key(&item.id))
+            .map(|item| {
+                [
+                    &item.time,
+                    &item.level,
+                    &item.origin,
+                    &item.tag,
+                    &item.content,
+                ]
+                .map(|field| escape_tsv(field))
+                .join("\t")
+            })
+            .collect();
+        let count = rows.len();
+        let content = std::iter::once("time\tlevel\torigin\ttag\tcontent".to_string())
+            .chain(rows)
+            .collect::<Vec<_>>()
+            .join("\n");
+        (content, count)
+    }
 
+    /// Returns how many logs were copied
+    fn yank_visible_logs_tsv(&self) -> Result<usize> {
         let mut clipboard = Clipboard::new()?;
-        let yank_content = self.make_yank_content(item);
+        let (yank_content, count) = self.make_tsv_yank_content();
         clipboard.set_text(&yank_content)?;
 
         log::debug!(
-            "Yanked log content to clipboard: {} chars",
+            "Yanked {} log items as TSV: {} chars",
+            count,
             yank_content.len()
         );
+        Ok(count)
+    }
 
-        Ok(())
+    /// Copies the value under the details cursor, returning that field's name
+    fn yank_details_field(&self) -> Result<Option<&'static str>> {
+        let Some(item) = self.selected_log() else {
+            return Ok(None);
+        };
+
+        let field = DETAIL_FIELDS[self.details_field];
+        let value = match field {
+            "Time" => &item.time,
+            "Level" => &item.level,
+            "Origin" => &item.origin,
+            "Tag" => &item.tag,
+            _ => &item.content,
+        };
+        Clipboard::new()?.set_text(value)?;
+        Ok(Some(field))
+    }
+
+    /// Copies the stack trace of the selected log, or its whole content when none is found.
+    /// Returns whether a trace was found, or `None` without a selection.
+    fn yank_stack_trace(&self) -> Result<Option<bool>> {
+        let Some(item) = self.selected_log() else {
+            return Ok(None);
+        };
+
+        let trace = item.stack_trace();
+        Clipboard::new()?.set_text(trace.unwrap_or(&item.content))?;
+        Ok(Some(trace.is_some()))
+    }
+
+    /// Closes the yank menu, copies the selection in the format on `row` and makes that
+    /// format what `y` copies from now on
+    fn pick_yank_format(&mut self, row: usize) {
+        self.yank_menu = None;
+        self.yank_format = YankFormat::ALL[row];
+        self.yank_as(self.yank_format);
+    }
+
+    /// Copies in `format` and reports the outcome in a toast
+    fn yank_as(&mut self, format: YankFormat) {
+        let toast = match format {
+            YankFormat::Markdown => match self.yank_current_log() {
+                Ok(0) => "No log selected".to_string(),
+                Ok(1) => "Yanked".to_string(),
+                Ok(count) => format!("Yanked {} logs", count),
+                Err(e) => format!("Clipboard unavailable: {}", e),
+            },
+            YankFormat::Raw => match self.yank_raw() {
+                Ok(0) => "No log selected".to_string(),
+                Ok(1) => "Yanked raw text".to_string(),
+                Ok(count) => format!("Yanked {} logs as raw text", count),
+                Err(e) => format!("Clipboard unavailable: {}", e),
+            },
+            YankFormat::KeyValue => match self.yank_key_value() {
+                Ok(0) => "No log selected".to_string(),
+                Ok(1) => "Yanked as key=value".to_string(),
+                Ok(count) => format!("Yanked {} logs as key=value", count),
+                Err(e) => format!("Clipboard unavailable: {}", e),
+            },
+            YankFormat::StackTrace => match self.yank_stack_trace() {
+                Ok(Some(true)) => "Yanked stack trace".to_string(),
+                Ok(Some(false)) => "No stack trace found; yanked the content".to_string(),
+                Ok(None) => "No log selected".to_string(),
+                Err(e) => format!("Clipboard unavailable: {}", e),
+            },
+            YankFormat::Tsv => match self.yank_visible_logs_tsv() {
+                Ok(count) => format!("Yanked {} logs as TSV", count),
+                Err(e) => format!("Clipboard unavailable: {}", e),
+            },
+        };
+        self.toast(toast);
+    }
+
+    /// Copies the selected logs as `[time] raw text`, returning how many were copied
+    fn yank_raw(&self) -> Result<usize> {
+        let items = self.selected_items();
+        if items.is_empty() {
+            return Ok(0);
+        }
+        Clipboard::new()?.set_text(self.make_range_yank_content(&items))?;
+        Ok(items.len())
+    }
+
+    /// The logs of the selected range, or the selected log alone, oldest first. Tag section
+    /// headers in the range are left out.
+    fn selected_items(&self) -> Vec<&LogItem> {
+        let items = &self.displaying_logs.items;
+        let total = items.len();
+        match self.selected_range() {
+            Some((lo, hi)) => items
+                [App::to_underlying_index(total, hi)..=App::to_underlying_index(total, lo)]
+                .iter()
+                .filter(|item| !self.group_headers.contains_key(&item.id))
+                .collect(),
+            None => self.selected_log().into_iter().collect(),
+        }
+    }
+
+    /// Copies the selected logs as `key=value` lines, returning how many were copied
+    fn yank_key_value(&self) -> Result<usize> {
+        let items = self.selected_items();
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let yank_content = items
+            .iter()
+            .map(|item| item.to_key_value())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Clipboard::new()?.set_text(&yank_content)?;
+        Ok(items.len())
+    }
+
+    /// Returns how many logs were copied: 0 without a selection
+    fn yank_current_log(&self) -> Result<usize> {
+        let items = self.selected_items();
+        let yank_content = match items[..] {
+            [] => return Ok(0),
+            [item] if self.selected_range().is_none() => self.make_yank_content(item),
+            // Oldest first, like the file
+            _ => self.make_range_yank_content(&items),
+        };
+        Clipboard::new()?.set_text(&yank_content)?;
+
+        log::debug!(
+            "Yanked {} log items to clipboard: {} chars",
+            items.len(),
+            yank_content.len()
+        );
+        Ok(items.len())
     }
 
     fn fold_logs(&mut self) {
-        log::debug!("Fold functionality not yet implemented");
+        if self.fold_threshold == 0 {
+            self.toast("Folding is off: fold_threshold is 0");
+            return;
+        }
+        self.fold_repeats = !self.fold_repeats;
+        self.apply_filter(false);
+        self.toast(if self.fold_repeats {
+            "Folding repeated logs"
+        } else {
+            "Showing every repeated log"
+        });
     }
 
     fn clear_logs(&mut self) {
         self.raw_logs.clear();
+        self.unread = 0;
+        self.tail = TailBuffer::default();
         self.displaying_logs = LogList::new(Vec::new());
+        self.selected_log_uuid = None;
+        self.selection_anchor = None;
         self.filter_input.clear();
+        self.field_filter = None;
+        self.hidden_levels.clear();
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
@@ -1051,16 +3264,28 @@ impl App {
                     return Ok(());
                 }
                 KeyCode::Enter => {
-                    self.apply_filter();
+                    // The list already reflects the input, just close it
                     self.filter_mode = false;
+                    self.filter_before_edit.clear();
+                    if self.filter_target == FilterTarget::Debug {
+                        return Ok(());
+                    }
+                    if let Some(e) = self.filter_error.clone() {
+                        self.toast(format!("{}: filtering by the whole text", e));
+                    } else if self.displaying_logs.items.is_empty() && !self.raw_logs.is_empty() {
+                        self.toast("No match");
+                    }
                     return Ok(());
                 }
                 KeyCode::Char(c) => {
-                    self.filter_input.push(c);
+                    self.target_filter_mut().push(c);
+                    self.apply_target_filter(true);
                     return Ok(());
                 }
                 KeyCode::Backspace => {
-                    self.filter_input.pop();
+                    if self.target_filter_mut().pop().is_some() {
+                        self.apply_target_filter(false);
+                    }
                     return Ok(());
                 }
                 _ => {}
@@ -1068,88 +3293,474 @@ impl App {
             return Ok(());
         }
 
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                log::debug!("Exit key pressed");
-                self.is_exiting = true;
-                return Ok(());
-            }
-            KeyCode::Char('c') => {
-                if key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                    self.is_exiting = true;
-                } else {
-                    self.clear_logs();
+        // Handle command palette input
+        if self.palette_mode {
+            match key.code {
+                KeyCode::Esc => self.palette_mode = false,
+                KeyCode::Enter => {
+                    self.palette_mode = false;
+                    let matches = action::matching_actions(&self.palette_input);
+                    if let Some(action) = matches.get(self.palette_selected) {
+                        return self.perform(*action);
+                    }
                 }
-                return Ok(());
-            }
-            KeyCode::Char('f') => {
-                self.fold_logs();
-                return Ok(());
-            }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.handle_log_item_scrolling(true, true)?;
-                return Ok(());
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.handle_log_item_scrolling(false, true)?;
-                return Ok(());
-            }
-            KeyCode::Char('g') => {
-                self.displaying_logs.select_first();
-                self.update_selected_uuid();
-                self.ensure_selection_visible()?;
-                self.update_logs_scrollbar_state();
-                return Ok(());
-            }
-            KeyCode::Char('G') => {
-                self.displaying_logs.select_last();
-                self.update_selected_uuid();
-                self.ensure_selection_visible()?;
-                self.update_logs_scrollbar_state();
-                return Ok(());
-            }
-            KeyCode::Char('/') => {
-                self.filter_mode = true;
-                self.filter_input.clear();
-                return Ok(());
-            }
-            KeyCode::Char('[') => {
-                // Decrease detail level (show less info) - non-circular
-                if self.detail_level > 0 {
-                    self.detail_level -= 1;
+                KeyCode::Up => self.palette_selected = self.palette_selected.saturating_sub(1),
+                KeyCode::Down => {
+                    let last = action::matching_actions(&self.palette_input)
+                        .len()
+                        .saturating_sub(1);
+                    self.palette_selected = (self.palette_selected + 1).min(last);
                 }
-                return Ok(());
-            }
-            KeyCode::Char(']') => {
-                // Increase detail level (show more info) - non-circular
-                if self.detail_level < 4 {
-                    self.detail_level += 1;
+                KeyCode::Char(c) => {
+                    self.palette_input.push(c);
+                    self.palette_selected = 0;
                 }
-                return Ok(());
-            }
-            KeyCode::Char('y') => {
-                // Yank (copy) the current log item content to clipboard
-                if let Err(e) = self.yank_current_log() {
-                    log::debug!("Failed to yank log content: {}", e);
+                KeyCode::Backspace => {
+                    self.palette_input.pop();
+                    self.palette_selected = 0;
                 }
-                return Ok(());
-            }
-            _ => {
-                return Ok(());
+                _ => {}
             }
+            return Ok(());
         }
-    }
-
-    fn set_focused_block(&mut self, block_id: uuid::Uuid) {
-        self.focused_block_id = Some(block_id);
-    }
-
-    fn clear_event(&mut self) {
-        self.event = None;
-    }
 
-    /// Find the index of a log item by its UUID
-    fn find_log_by_uuid(&self, uuid: &uuid::Uuid) -> Option<usize> {
+        // Handle preset naming and the preset list
+        if let Some(name) = &mut self.preset_name_input {
+            match key.code {
+                KeyCode::Esc => self.preset_name_input = None,
+                KeyCode::Enter => {
+                    let name = name.trim().to_string();
+                    self.preset_name_input = None;
+                    if !name.is_empty() {
+                        self.save_preset(name);
+                    }
+                }
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        if let Some(row) = self.help {
+            let last = action::KEY_HELP.len() - 1;
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('?' | 'q') => self.help = None,
+                KeyCode::Up | KeyCode::Char('k') => self.help = Some(row.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => self.help = Some((row + 1).min(last)),
+                _ => {}
+            }
+            return Ok(());
+        }
+        if let Some(row) = self.yank_menu {
+            let last = YankFormat::ALL.len() - 1;
+            match key.code {
+                KeyCode::Esc => self.yank_menu = None,
+                KeyCode::Enter => self.pick_yank_format(row),
+                KeyCode::Char(c @ '1'..='9') if (c as usize - '1' as usize) <= last => {
+                    self.pick_yank_format(c as usize - '1' as usize)
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.yank_menu = Some(row.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => self.yank_menu = Some((row + 1).min(last)),
+                _ => {}
+            }
+            return Ok(());
+        }
+        if let Some(row) = self.preset_picker {
+            let last = self.presets.presets.len().saturating_sub(1);
+            match key.code {
+                KeyCode::Esc => self.preset_picker = None,
+                KeyCode::Enter => {
+                    self.preset_picker = None;
+                    self.apply_preset(row);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.preset_picker = Some(row.saturating_sub(1))
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.preset_picker = Some((row + 1).min(last))
+                }
+                KeyCode::Delete | KeyCode::Char('d') => {
+                    if let Some(preset) = self.presets.remove(row) {
+                        self.save_presets();
+                        self.toast(format!("Deleted preset '{}'", preset.name));
+                    }
+                    let last = self.presets.presets.len().saturating_sub(1);
+                    self.preset_picker = Some(row.min(last));
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Handle the raw file search and its results
+        if let Some(pattern) = &mut self.grep_input {
+            match key.code {
+                KeyCode::Esc => self.grep_input = None,
+                KeyCode::Enter => {
+                    let pattern = pattern.clone();
+                    self.grep_input = None;
+                    if !pattern.is_empty() {
+                        self.grep_file(pattern);
+                    }
+                }
+                KeyCode::Char(c) => pattern.push(c),
+                KeyCode::Backspace => {
+                    pattern.pop();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        if let Some(grep) = &mut self.file_grep {
+            match key.code {
+                KeyCode::Esc => self.file_grep = None,
+                KeyCode::Enter => self.jump_to_grep_match()?,
+                KeyCode::Up | KeyCode::Char('k') => grep.selected = grep.selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    grep.selected = (grep.selected + 1).min(grep.matches.len() - 1)
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Handle origin/tag picker input
+        if self.picker_mode {
+            match key.code {
+                KeyCode::Esc => self.picker_mode = false,
+                KeyCode::Enter => self.select_picker_row(),
+                KeyCode::Tab => {
+                    self.picker_field = match self.picker_field {
+                        Field::Origin => Field::Tag,
+                        _ => Field::Origin,
+                    };
+                    self.picker_selected = 0;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.picker_selected = self.picker_selected.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let last = self.picker_values().len();
+                    self.picker_selected = (self.picker_selected + 1).min(last);
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match Action::from_key(key) {
+            Some(action) => self.perform(action),
+            None => Ok(()),
+        }
+    }
+
+    /// Inserts a bracketed paste into whichever text input is open. Every input is a single
+    /// line, so pasted line breaks become spaces.
+    fn handle_paste(&mut self, text: &str) {
+        let text = text.lines().collect::<Vec<_>>().join(" ");
+        if self.filter_mode {
+            self.target_filter_mut().push_str(&text);
+            self.apply_target_filter(true);
+        } else if self.palette_mode {
+            self.palette_input.push_str(&text);
+            self.palette_selected = 0;
+        } else if let Some(name) = &mut self.preset_name_input {
+            name.push_str(&text);
+        } else if let Some(pattern) = &mut self.grep_input {
+            pattern.push_str(&text);
+        } else {
+            log::debug!(
+                "Ignored a paste of {} chars: no text input is open",
+                text.len()
+            );
+        }
+    }
+
+    fn perform(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::Quit => {
+                log::debug!("Exit key pressed");
+                self.is_exiting = true;
+            }
+            Action::ClearLogs => self.clear_logs(),
+            Action::Reload => self.reload_logs()?,
+            Action::FoldLogs => self.fold_logs(),
+            Action::SelectNext if self.is_details_block_focused()? => {
+                self.details_field = (self.details_field + 1).min(DETAIL_FIELDS.len() - 1);
+            }
+            Action::SelectPrevious if self.is_details_block_focused()? => {
+                self.details_field = self.details_field.saturating_sub(1);
+            }
+            Action::SelectNext => self.handle_log_item_scrolling(true, self.wrap_around)?,
+            Action::SelectPrevious => self.handle_log_item_scrolling(false, self.wrap_around)?,
+            Action::SelectNewest => {
+                self.autoscroll_held_off = false;
+                self.unread = 0;
+                self.displaying_logs.select_first();
+                self.update_selected_uuid();
+                self.selection_anchor = None;
+                self.arm_copy_on_select();
+                self.ensure_selection_visible()?;
+                self.update_logs_scrollbar_state();
+                self.update_autoscroll_state();
+            }
+            Action::SelectOldest => {
+                self.unread = 0;
+                self.displaying_logs.select_last();
+                self.update_selected_uuid();
+                self.selection_anchor = None;
+                self.arm_copy_on_select();
+                self.ensure_selection_visible()?;
+                self.update_logs_scrollbar_state();
+                self.update_autoscroll_state();
+            }
+            Action::ToggleAutoscroll => {
+                if self.autoscroll {
+                    self.autoscroll = false;
+                    self.autoscroll_held_off = true;
+                } else {
+                    self.resume_autoscroll();
+                }
+                log::debug!("Autoscroll {}", if self.autoscroll { "on" } else { "off" });
+            }
+            Action::GoLive => self.resume_autoscroll(),
+            Action::RevealSelection => {
+                self.ensure_selection_visible()?;
+                self.update_autoscroll_state();
+            }
+            Action::CenterSelection => {
+                self.center_selection();
+                self.update_autoscroll_state();
+            }
+            Action::ToggleFilter => {
+                self.filter_suspended = !self.filter_suspended;
+                // The selection is kept by id, so it stays on the same log when it's shown
+                self.apply_filter(false);
+            }
+            Action::Filter => {
+                self.filter_mode = true;
+                self.filter_target = if self.focused_block_id == Some(self.debug_block.id()) {
+                    FilterTarget::Debug
+                } else {
+                    // Editing the LOGS filter means wanting to see it
+                    self.filter_suspended = false;
+                    FilterTarget::Logs
+                };
+                self.filter_before_edit = std::mem::take(self.target_filter_mut());
+                self.apply_target_filter(false);
+            }
+            Action::PickField => {
+                self.picker_mode = true;
+                if let Some(field_filter) = &self.field_filter {
+                    self.picker_field = field_filter.field;
+                }
+                // Start on the active value so Enter keeps it
+                self.picker_selected = self
+                    .field_filter
+                    .as_ref()
+                    .filter(|field_filter| field_filter.field == self.picker_field)
+                    .and_then(|field_filter| {
+                        self.picker_values()
+                            .iter()
+                            .position(|(value, _)| *value == field_filter.value)
+                    })
+                    .map_or(0, |row| row + 1);
+            }
+            Action::DecreaseDetail => {
+                // Show less info - non-circular
+                if self.detail_level > 0 {
+                    self.detail_level -= 1;
+                }
+            }
+            Action::IncreaseDetail => {
+                // Show more info - non-circular
+                if self.detail_level < MAX_DETAIL_LEVEL {
+                    self.detail_level += 1;
+                }
+            }
+            Action::ToggleHexDump => {
+                if self.is_details_block_focused()? {
+                    self.details_hex = !self.details_hex;
+                    self.details_inspect = false;
+                    self.details_block.set_scroll_position(0);
+                    self.details_block.set_h_scroll_position(0);
+                } else {
+                    self.toast("Focus LOG DETAILS to toggle the hex dump");
+                }
+            }
+            Action::ToggleInspectParse => {
+                if self.is_details_block_focused()? {
+                    self.details_inspect = !self.details_inspect;
+                    self.details_hex = false;
+                    self.details_block.set_scroll_position(0);
+                    self.details_block.set_h_scroll_position(0);
+                } else {
+                    self.toast("Focus LOG DETAILS to inspect how the log was parsed");
+                }
+            }
+            Action::ToggleJson => {
+                self.details_json = !self.details_json;
+                self.details_json_cache = None;
+                self.details_wrap_cache = None;
+            }
+            Action::ToggleDiff => self.details_diff = !self.details_diff,
+            Action::ToggleWrap => {
+                self.wrap = !self.wrap;
+                self.details_block.set_h_scroll_position(0);
+                if let Some(path) = &self.config_path
+                    && let Err(e) = Config::save_wrap(path, self.wrap)
+                {
+                    self.toast(format!("{:#}", e));
+                }
+            }
+            Action::PanDetailsLeft => self.handle_details_h_scrolling(false)?,
+            Action::PanDetailsRight => self.handle_details_h_scrolling(true)?,
+            Action::ToggleLineNumbers => self.show_line_numbers = !self.show_line_numbers,
+            Action::ToggleTimeSort => {
+                self.sort_by_time = !self.sort_by_time;
+                self.apply_filter(false);
+            }
+            Action::ToggleAgeFade => self.age_fade = !self.age_fade,
+            Action::ToggleTagGroups => {
+                self.group_by_tag = !self.group_by_tag;
+                self.apply_filter(false);
+            }
+            Action::ToggleGroup => {
+                if self.group_by_tag {
+                    self.toggle_selected_group();
+                }
+            }
+            Action::OpenSource => match self.selected_source_location() {
+                // The terminal is only reachable from run(), which opens it next
+                Some((path, line)) => self.pending_command = self.editor_command(&path, line),
+                None => self.toast("No file:line in this log"),
+            },
+            Action::Shell => {
+                let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+                self.pending_command = Some(Command::new(shell));
+            }
+            Action::ToggleHud => self.show_hud = !self.show_hud,
+            Action::ToggleMouseCapture => {
+                self.mouse_capture = !self.mouse_capture;
+                if !self.mouse_capture {
+                    self.toast("Mouse released: select text with the terminal, m to take it back");
+                }
+            }
+            Action::SavePreset => self.preset_name_input = Some(String::new()),
+            Action::GrepFile => self.grep_input = Some(String::new()),
+            Action::LoadPreset => {
+                if self.presets.presets.is_empty() {
+                    self.toast("No presets yet: P saves the current filter as one");
+                } else {
+                    self.preset_picker = Some(0);
+                }
+            }
+            Action::RecallPreset(slot) => self.apply_preset(slot),
+            Action::FocusNext => {
+                let order = [
+                    self.logs_block.id(),
+                    self.details_block.id(),
+                    self.debug_block.id(),
+                ];
+                let next = order
+                    .iter()
+                    .position(|id| Some(*id) == self.focused_block_id)
+                    .map_or(0, |i| (i + 1) % order.len());
+                self.set_focused_block(order[next]);
+            }
+            Action::Yank if self.is_details_block_focused()? => match self.yank_details_field() {
+                Ok(Some(field)) => self.toast(format!("Yanked {}", field)),
+                Ok(None) => self.toast("No log selected"),
+                Err(e) => self.toast(format!("Clipboard unavailable: {}", e)),
+            },
+            Action::Yank => self.yank_as(self.yank_format),
+            Action::YankMenu => {
+                let row = YankFormat::ALL.iter().position(|f| *f == self.yank_format);
+                self.yank_menu = row;
+            }
+            Action::YankStackTrace => self.yank_as(YankFormat::StackTrace),
+            Action::YankKeyValue => self.yank_as(YankFormat::KeyValue),
+            Action::YankTsv => self.yank_as(YankFormat::Tsv),
+            Action::CommandPalette => {
+                self.palette_mode = true;
+                self.palette_input.clear();
+                self.palette_selected = 0;
+            }
+            Action::Help => self.help = Some(0),
+        }
+        Ok(())
+    }
+
+    /// The first `path:line` of the selected log's content naming a file that exists, as
+    /// given or relative to the log directory
+    fn selected_source_location(&self) -> Option<(PathBuf, usize)> {
+        self.selected_log()?
+            .source_locations()
+            .into_iter()
+            .find_map(|(path, line)| {
+                [PathBuf::from(path), self.log_dir_path.join(path)]
+                    .into_iter()
+                    .find(|candidate| candidate.is_file())
+                    .map(|found| (found, line))
+            })
+    }
+
+    /// `$VISUAL`/`$EDITOR` (`vi` without either) opening `path` at `line`
+    fn editor_command(&mut self, path: &Path, line: usize) -> Option<Command> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut words = editor.split_whitespace();
+        let Some(program) = words.next() else {
+            self.toast("$EDITOR is empty");
+            return None;
+        };
+
+        let mut command = Command::new(program);
+        command.args(words).arg(format!("+{}", line)).arg(path);
+        Some(command)
+    }
+
+    /// Leaves the TUI, runs `command` on the bare terminal and takes the terminal back once
+    /// it exits, redrawing everything
+    fn run_outside_tui(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        mut command: Command,
+    ) -> Result<()> {
+        tui::restore_terminal()?;
+        let status = command.status();
+        tui::enter_tui_mode()?;
+        terminal.clear()?;
+
+        let program = command.get_program().to_string_lossy().into_owned();
+        match status {
+            Ok(status) if status.success() => log::debug!("{} exited", program),
+            Ok(status) => self.toast(format!("{} exited with {}", program, status)),
+            Err(e) => self.toast(format!("Failed to run {}: {}", program, e)),
+        }
+        Ok(())
+    }
+
+    /// Shows `message` over the footer for `TOAST_DURATION`, replacing any earlier toast
+    fn toast(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        log::debug!("{}", message);
+        self.toast = Some((message, Instant::now() + TOAST_DURATION));
+    }
+
+    fn set_focused_block(&mut self, block_id: uuid::Uuid) {
+        self.focused_block_id = Some(block_id);
+    }
+
+    fn clear_event(&mut self) {
+        self.event = None;
+    }
+
+    /// Find the index of a log item by its UUID
+    fn find_log_by_uuid(&self, uuid: &uuid::Uuid) -> Option<usize> {
         self.displaying_logs
             .items
             .iter()
@@ -1178,6 +3789,61 @@ impl App {
         }
     }
 
+    /// Position in `displaying_logs.items` of the selected log. Tag section headers aren't
+    /// logs, so with one selected there is none.
+    fn selected_log_index(&self) -> Option<usize> {
+        let items = &self.displaying_logs.items;
+        let index = App::to_underlying_index(items.len(), self.displaying_logs.state.selected()?);
+        items
+            .get(index)
+            .filter(|item| !self.group_headers.contains_key(&item.id))
+            .map(|_| index)
+    }
+
+    fn selected_log(&self) -> Option<&LogItem> {
+        self.selected_log_index()
+            .map(|index| &self.displaying_logs.items[index])
+    }
+
+    /// Visual index bounds (inclusive, low first) of the Shift+Click range, if one is active
+    fn selected_range(&self) -> Option<(usize, usize)> {
+        let anchor_index = self.find_log_by_uuid(&self.selection_anchor?)?;
+        let selected = self.displaying_logs.state.selected()?;
+        let anchor = App::to_visual_index(self.displaying_logs.items.len(), anchor_index);
+        Some((anchor.min(selected), anchor.max(selected)))
+    }
+
+    /// Starts the wait before copy-on-select copies the selected log. Called when the user
+    /// moves the selection, so the selection made on load or after a filter copies nothing.
+    fn arm_copy_on_select(&mut self) {
+        self.pending_copy = self
+            .selected_log()
+            .map(|item| item.id)
+            .filter(|id| self.copy_on_select && Some(*id) != self.copied_log_uuid)
+            .map(|id| (id, Instant::now()));
+    }
+
+    /// The raw text copy-on-select should copy now: the selected log's, once the selection
+    /// the user moved to has stayed on it for `COPY_ON_SELECT_SETTLE`
+    fn settled_copy(&mut self, now: Instant) -> Option<String> {
+        let (id, since) = self.pending_copy?;
+        if !self.copy_on_select || Some(id) != self.selected_log_uuid {
+            // Turned off, or the selection moved on without the user
+            self.pending_copy = None;
+            return None;
+        }
+        if now.duration_since(since) < COPY_ON_SELECT_SETTLE {
+            return None;
+        }
+        self.pending_copy = None;
+        self.copied_log_uuid = Some(id);
+        self.displaying_logs
+            .items
+            .iter()
+            .find(|item| item.id == id)
+            .map(|item| item.raw_content.clone())
+    }
+
     /// Update the tracked UUID when selection changes
     fn update_selected_uuid(&mut self) {
         let Some(visual_index) = self.displaying_logs.state.selected() else {
@@ -1203,6 +3869,19 @@ impl App {
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        // Below this size the fixed-height rows leave zero-height panels behind
+        if area.height < 10 || area.width < 20 {
+            let [message_area] = Layout::vertical([Constraint::Length(1)])
+                .flex(layout::Flex::Center)
+                .areas(area);
+            Paragraph::new("Terminal too small")
+                .bold()
+                .centered()
+                .render(message_area, buf);
+            self.clear_event();
+            return;
+        }
+
         let [header_area, main_area, debug_area, footer_area] = Layout::vertical([
             Constraint::Length(1),
             Constraint::Fill(1),
@@ -1221,6 +3900,1598 @@ impl Widget for &mut App {
         self.render_debug_logs(debug_area, buf).unwrap();
         self.render_footer(footer_area, buf).unwrap();
 
+        if self.show_hud {
+            self.render_hud(main_area, buf).unwrap();
+        }
+        if self.palette_mode {
+            self.render_command_palette(main_area, buf).unwrap();
+        }
+        if self.picker_mode {
+            self.render_field_picker(main_area, buf).unwrap();
+        }
+        if let Some(row) = self.preset_picker {
+            self.render_preset_picker(main_area, buf, row).unwrap();
+        }
+        if let Some(row) = self.yank_menu {
+            self.render_yank_menu(main_area, buf, row).unwrap();
+        }
+        if let Some(row) = self.help {
+            self.render_help(main_area, buf, row).unwrap();
+        }
+        if let Some(grep) = &self.file_grep {
+            self.render_file_grep(main_area, buf, grep).unwrap();
+        }
+
         self.clear_event();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{ffi::OsStr, fs};
+
+    const LOG_LINES: &str = "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+                             ## 2024-01-01 10:00:01 [main] INFO ## [ui] drawn\n\
+                             ## 2024-01-01 10:00:02 [main] WARN ## [net] retrying\n";
+
+    /// A fresh directory holding the file `name`, and that file's path
+    fn temp_file(name: &str, contents: impl AsRef<[u8]>) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    /// An app over a preview.log holding `lines`, with the logs read in. Keep the directory
+    /// alive for as long as the app is used.
+    fn app_with(lines: &str, config: &Config) -> (tempfile::TempDir, App) {
+        let (dir, _) = temp_file("preview.log", lines);
+        let mut app = App::new(dir.path().to_path_buf(), None, config).unwrap();
+        app.update_logs().unwrap();
+        (dir, app)
+    }
+
+    /// `n` INFO logs at the same second, reading "line 0" to "line {n - 1}"
+    fn numbered_lines(n: usize) -> String {
+        (0..n)
+            .map(|i| format!("## 2024-01-01 10:00:00 [main] INFO ## [net] line {}\n", i))
+            .collect()
+    }
+
+    /// Color of the first `[` on the first row of `buf` showing `needle`, where a LOGS row's
+    /// time starts
+    fn row_fg(buf: &Buffer, needle: &str) -> Color {
+        let area = buf.area;
+        let row = (0..area.height)
+            .find(|&y| {
+                let text: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                text.contains(needle)
+            })
+            .unwrap();
+        let x = (0..area.width)
+            .find(|&x| buf[(x, row)].symbol() == "[")
+            .unwrap();
+        buf[(x, row)].fg
+    }
+
+    fn filtered_raw_ids(app: &App) -> Vec<uuid::Uuid> {
+        let query = Query::phrase(&app.filter_input);
+        app.raw_logs
+            .iter()
+            .filter(|item| query.matches(item))
+            .map(|item| item.id)
+            .collect()
+    }
+
+    fn displayed_ids(app: &App) -> Vec<uuid::Uuid> {
+        app.displaying_logs
+            .items
+            .iter()
+            .map(|item| item.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_truncation_keeps_filter_coherent() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+        assert_eq!(app.raw_logs.len(), 3);
+
+        app.filter_input = "net".to_string();
+        app.apply_filter(false);
+        assert_eq!(displayed_ids(&app), filtered_raw_ids(&app));
+
+        // Archive: the file is emptied, so nothing is re-read after the reset
+        fs::write(&log_path, "").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.last_len, 0);
+        assert_eq!(displayed_ids(&app), filtered_raw_ids(&app));
+
+        // Fresh content after the truncation is appended and still filtered
+        fs::write(
+            &log_path,
+            "## 2024-01-01 10:00:03 [main] ERROR ## [net] dropped\n",
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 4);
+        assert_eq!(displayed_ids(&app), filtered_raw_ids(&app));
+        assert_eq!(app.displaying_logs.items.len(), 3);
+    }
+
+    #[test]
+    fn test_large_delta_is_read_in_item_chunks() {
+        let (_dir, log_path) = temp_file("preview.log", LOG_LINES);
+        let len = LOG_LINES.len() as u64;
+        let parser = LogParser::default();
+
+        // The window ends after the second item's timestamp, so only the first is read
+        let first_len = LOG_LINES.find("\n## ").unwrap() as u64 + 1;
+        let mut tail = TailBuffer::default();
+        let (replaced, items, read_to) =
+            map_and_process_delta(&parser, &mut tail, &log_path, 0, len, first_len + 30).unwrap();
+        assert_eq!((replaced, items.len()), (0, 1));
+        assert_eq!(read_to, first_len);
+
+        // The first item may have grown, so it is parsed again with the rest
+        let (replaced, items, read_to) =
+            map_and_process_delta(&parser, &mut tail, &log_path, read_to, len, len).unwrap();
+        assert_eq!((replaced, items.len()), (1, 3));
+        assert_eq!(read_to, len);
+
+        // An item bigger than the chunk can't be cut, so the rest is read in one go
+        let mut tail = TailBuffer::default();
+        let (_, items, read_to) =
+            map_and_process_delta(&parser, &mut tail, &log_path, 0, len, 5).unwrap();
+        assert_eq!((items.len(), read_to), (3, len));
+    }
+
+    #[test]
+    fn test_loads_gzipped_log_without_tailing() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(LOG_LINES.as_bytes()).unwrap();
+        let (dir, log_path) = temp_file("preview.1.log.gz", encoder.finish().unwrap());
+
+        let mut app = App::new(
+            dir.path().to_path_buf(),
+            Some(log_path.clone()),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(app.is_archive);
+        assert!(!app.follow_latest);
+
+        app.load_compressed_log().unwrap();
+        assert_eq!(app.raw_logs.len(), 3);
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 3);
+    }
+
+    #[test]
+    fn test_corrupt_gzip_is_an_error() {
+        let (dir, log_path) = temp_file("preview.1.log.gz", LOG_LINES);
+        let mut app =
+            App::new(dir.path().to_path_buf(), Some(log_path), &Config::default()).unwrap();
+        assert!(app.load_compressed_log().is_err());
+    }
+
+    /// Rows of the LOGS view showing logs, besides the marker rows
+    fn list_height(app: &App) -> usize {
+        let content_rect = app
+            .logs_block
+            .get_content_rect(app.last_logs_area.unwrap(), false);
+        app.logs_list_height(content_rect.height as usize)
+    }
+
+    fn render_text(app: &mut App, width: u16, height: u16) -> String {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        app.render(area, &mut buf);
+        buf.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_too_small_terminal_shows_message() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        assert!(render_text(&mut app, 40, 5).contains("Terminal too small"));
+        // Growing the terminal again brings the normal layout back
+        let text = render_text(&mut app, 80, 24);
+        assert!(!text.contains("Terminal too small"));
+        assert!(text.contains("LOGS"));
+    }
+
+    #[test]
+    fn test_dump_prints_last_lines() {
+        let (_dir, log_path) = temp_file("preview.log", LOG_LINES);
+
+        let mut out = Vec::new();
+        dump(&Config::default(), Some(log_path), 2, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[2024-01-01 10:00:01] drawn\n[2024-01-01 10:00:02] retrying\n"
+        );
+    }
+
+    #[test]
+    fn test_dump_uses_detail_level_flag() {
+        use clap::Parser;
+
+        let (_dir, log_path) = temp_file("preview.log", LOG_LINES);
+
+        let mut config = Config::default();
+        crate::cli::Cli::try_parse_from(["termlog", "--detail-level", "3"])
+            .unwrap()
+            .apply_to(&mut config);
+        let mut out = Vec::new();
+        dump(&config, Some(log_path), 1, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[2024-01-01 10:00:02] [WARN] [main] retrying\n"
+        );
+    }
+
+    #[test]
+    fn test_picker_filters_by_exact_tag() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.perform(Action::PickField).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Tab)).unwrap();
+        // Row 1 is the most common tag, "net"
+        app.handle_key(KeyEvent::from(KeyCode::Down)).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+
+        assert!(!app.picker_mode);
+        assert_eq!(app.displaying_logs.items.len(), 2);
+        assert!(
+            app.displaying_logs
+                .items
+                .iter()
+                .all(|item| item.tag == "net")
+        );
+
+        // "All" clears it again
+        app.perform(Action::PickField).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Up)).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(app.displaying_logs.items.len(), 3);
+    }
+
+    #[test]
+    fn test_time_sort_reorders_display_only() {
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:05 [main] INFO ## [net] late\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] early\n",
+            &Config::default(),
+        );
+        let contents = |app: &App| -> Vec<String> {
+            app.displaying_logs
+                .items
+                .iter()
+                .map(|item| item.content.clone())
+                .collect()
+        };
+        assert_eq!(contents(&app), ["late", "early"]);
+
+        app.perform(Action::ToggleTimeSort).unwrap();
+        assert_eq!(contents(&app), ["early", "late"]);
+        assert_eq!(app.raw_logs[0].content, "late");
+    }
+
+    #[test]
+    fn test_tsv_yank_escapes_fields() {
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first\tcolumn\nsecond line\n\
+             ## 2024-01-01 10:00:01 [main] WARN ## [ui] newest\n",
+            &Config::default(),
+        );
+
+        let table = "time\tlevel\torigin\ttag\tcontent\n\
+                     2024-01-01 10:00:01\tWARN\tmain\tui\tnewest\n\
+                     2024-01-01 10:00:00\tINFO\tmain\tnet\tfirst\\tcolumn\\nsecond line";
+        assert_eq!(app.make_tsv_yank_content(), (table.to_string(), 2));
+
+        // Tag section headers are neither rows nor counted
+        app.perform(Action::ToggleTagGroups).unwrap();
+        assert_eq!(app.displaying_logs.items.len(), 4);
+        let (content, count) = app.make_tsv_yank_content();
+        assert_eq!(content.lines().count(), 3);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_selected_wide_row_is_highlighted_to_the_edge() {
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [ui] 日本語 👨\u{200d}👩\u{200d}👧 cafe\u{301}\u{301}\n",
+            &Config::default(),
+        );
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        app.render(area, &mut buf);
+
+        // Same split as render_logs: scrollbar column on the right, then the block's inner rect
+        let logs_area = app.last_logs_area.unwrap();
+        let content_area = Rect {
+            width: logs_area.width - 1,
+            ..logs_area
+        };
+        let inner = app.logs_block.get_content_rect(content_area, true);
+        // The newest log is drawn under the live marker
+        let row = inner.y + 1;
+        let selected_bg = theme::SELECTED_STYLE.bg;
+        // The cell after a wide glyph is reset by ratatui; the glyph's own bg covers it
+        let mut x = inner.left();
+        while x < inner.right() {
+            let cell = &buf[(x, row)];
+            assert_eq!(cell.bg, selected_bg.unwrap(), "column {}", x);
+            x += cell.symbol().width().max(1) as u16;
+        }
+        assert_eq!(x, inner.right());
+        assert_ne!(buf[(inner.right(), row)].bg, selected_bg.unwrap());
+    }
+
+    #[test]
+    fn test_recenter_keeps_selection() {
+        let (_dir, mut app) = app_with(&numbered_lines(100), &Config::default());
+        render_text(&mut app, 80, 40);
+
+        app.displaying_logs.state.select(Some(50));
+        app.update_selected_uuid();
+        app.logs_block.set_scroll_position(0);
+
+        app.perform(Action::RevealSelection).unwrap();
+        let revealed = app.logs_block.get_scroll_position();
+        let visible_height = list_height(&app);
+        assert!(revealed <= 50 && 50 < revealed + visible_height);
+
+        app.perform(Action::CenterSelection).unwrap();
+        assert_eq!(
+            app.logs_block.get_scroll_position(),
+            50 - visible_height / 2
+        );
+        assert_eq!(app.displaying_logs.state.selected(), Some(50));
+        assert!(!app.autoscroll);
+    }
+
+    #[test]
+    fn test_center_mode_recenters_near_edges() {
+        let config = Config {
+            center_selection: true,
+            center_margin: 3,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&numbered_lines(100), &config);
+        render_text(&mut app, 80, 40);
+        let height = app
+            .logs_block
+            .get_content_rect(app.last_logs_area.unwrap(), false)
+            .height as usize;
+        let visible_height = list_height(&app);
+
+        // Stepping down from the top: nothing moves until the margin is reached
+        for _ in 0..visible_height - 4 {
+            app.perform(Action::SelectNext).unwrap();
+        }
+        assert_eq!(app.logs_block.get_scroll_position(), 0);
+        app.perform(Action::SelectNext).unwrap();
+        let selected = app.displaying_logs.state.selected().unwrap();
+        assert_eq!(
+            app.logs_block.get_scroll_position(),
+            selected - visible_height / 2
+        );
+
+        // At the far end the view clamps instead of centering past the last item
+        app.perform(Action::SelectOldest).unwrap();
+        assert_eq!(app.logs_block.get_scroll_position(), 100 - height);
+    }
+
+    #[test]
+    fn test_scrolloff_keeps_context_rows() {
+        let config = Config {
+            scrolloff: Some(3),
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&numbered_lines(100), &config);
+        render_text(&mut app, 80, 40);
+
+        for _ in 0..list_height(&app) {
+            app.perform(Action::SelectNext).unwrap();
+        }
+        let selected = app.displaying_logs.state.selected().unwrap();
+        let view_end = app.logs_block.get_scroll_position() + list_height(&app) - 1;
+        assert_eq!(view_end - selected, 3);
+
+        // Stop short of the top, where the list boundary wins over the margin
+        for _ in 0..selected - 5 {
+            app.perform(Action::SelectPrevious).unwrap();
+        }
+        let selected = app.displaying_logs.state.selected().unwrap();
+        assert_eq!(selected - app.logs_block.get_scroll_position(), 3);
+    }
+
+    #[test]
+    fn test_details_only_builds_visible_rows() {
+        let rows: String = (0..200).map(|i| format!("row {}\n", i)).collect();
+        let (_dir, mut app) = app_with(
+            &format!(
+                "## 2024-01-01 10:00:00 [main] INFO ## [net] start\n{}",
+                rows
+            ),
+            &Config::default(),
+        );
+        render_text(&mut app, 100, 40);
+        // Time/Level/Origin/Tag/Content headers, "start", then the 200 rows
+        assert_eq!(app.details_block.get_lines_count(), 5 + 201);
+
+        app.details_block.set_scroll_position(5 + 101);
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("row 100"));
+        assert!(!text.contains("row 99 "));
+        assert_eq!(app.details_block.get_lines_count(), 5 + 201);
+    }
+
+    #[test]
+    fn test_details_wrap_is_cached_per_item_and_width() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let cache_key = |app: &App| app.details_wrap_cache.as_ref().map(|c| (c.id, c.width));
+
+        render_text(&mut app, 100, 40);
+        let key = cache_key(&app);
+        assert_eq!(key.map(|(id, _)| id), app.selected_log_uuid);
+        render_text(&mut app, 100, 40);
+        assert_eq!(cache_key(&app), key);
+
+        render_text(&mut app, 80, 40);
+        assert_ne!(cache_key(&app), key);
+
+        app.perform(Action::SelectNext).unwrap();
+        render_text(&mut app, 80, 40);
+        assert_eq!(cache_key(&app).map(|(id, _)| id), app.selected_log_uuid);
+
+        // Same item and width, but other text: wrapped again rather than sliced past its end
+        let index = app.selected_log_index().unwrap();
+        app.displaying_logs.items[index].content = "tiny".to_string();
+        assert!(render_text(&mut app, 80, 40).contains("tiny"));
+        app.displaying_logs.items[index].content = "a much longer line than it was".to_string();
+        assert!(render_text(&mut app, 80, 40).contains("a much longer line than it was"));
+    }
+
+    #[test]
+    fn test_hex_dump_needs_details_focus() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.set_focused_block(app.logs_block.id());
+
+        app.perform(Action::ToggleHexDump).unwrap();
+        assert!(!render_text(&mut app, 100, 40).contains("Raw bytes"));
+
+        app.set_focused_block(app.details_block.id());
+        app.perform(Action::ToggleHexDump).unwrap();
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("Raw bytes"));
+        assert!(text.contains("00000000  5b 6d 61 69 6e 5d"));
+    }
+
+    #[test]
+    fn test_hover_focus_can_be_turned_off_but_clicks_focus() {
+        let text = |app: &mut App| render_text(app, 80, 24);
+        let mouse_on_details = |app: &mut App, kind| {
+            let details_row = text(app)
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(80)
+                .position(|row| row.iter().collect::<String>().contains("LOG DETAILS"));
+            app.event = Some(MouseEvent {
+                kind,
+                column: 10,
+                row: details_row.unwrap() as u16 + 2,
+                modifiers: crossterm::event::KeyModifiers::NONE,
+            });
+            text(app);
+        };
+
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.set_focused_block(app.logs_block.id());
+        mouse_on_details(&mut app, MouseEventKind::Moved);
+        assert!(app.is_details_block_focused().unwrap());
+
+        let config = Config {
+            focus_follows_mouse: Some(false),
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(LOG_LINES, &config);
+        app.set_focused_block(app.logs_block.id());
+        mouse_on_details(&mut app, MouseEventKind::Moved);
+        assert!(app.is_log_block_focused().unwrap());
+
+        // Clicks and Tab still move focus along
+        let click = MouseEventKind::Down(crossterm::event::MouseButton::Left);
+        mouse_on_details(&mut app, click);
+        assert!(app.is_details_block_focused().unwrap());
+        app.perform(Action::FocusNext).unwrap();
+        app.perform(Action::FocusNext).unwrap();
+        assert!(app.is_log_block_focused().unwrap());
+    }
+
+    #[test]
+    fn test_animation_frames_follow_the_clock() {
+        let (_dir, mut app) = app_with("", &Config::default());
+        app.started_at = Instant::now() - Duration::from_millis(250);
+        assert_eq!(app.animation_frame(Duration::from_millis(100)), 2);
+        assert_eq!(app.animation_frame(Duration::from_secs(1)), 0);
+    }
+
+    #[test]
+    fn test_toast_covers_footer_until_it_expires() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.set_focused_block(app.logs_block.id());
+
+        app.perform(Action::ToggleHexDump).unwrap();
+        let text = render_text(&mut app, 200, 24);
+        assert!(text.contains("Focus LOG DETAILS to toggle the hex dump"));
+        assert!(!text.contains("q: quit"));
+
+        app.toast.as_mut().unwrap().1 = Instant::now();
+        let text = render_text(&mut app, 200, 24);
+        assert!(!text.contains("Focus LOG DETAILS"));
+        assert!(text.contains("jk↑↓: nav"));
+    }
+
+    #[test]
+    fn test_details_cursor_moves_over_fields() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let selected = app.displaying_logs.state.selected();
+
+        app.set_focused_block(app.logs_block.id());
+        assert!(!render_text(&mut app, 100, 40).contains("▸"));
+
+        app.set_focused_block(app.details_block.id());
+        assert!(render_text(&mut app, 100, 40).contains("▸ Time:"));
+        app.perform(Action::SelectNext).unwrap();
+        app.perform(Action::SelectNext).unwrap();
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("▸ Origin:"));
+        assert!(text.contains("  Time:"));
+        // The LOGS selection stays put while the cursor moves
+        assert_eq!(app.displaying_logs.state.selected(), selected);
+
+        for _ in 0..10 {
+            app.perform(Action::SelectNext).unwrap();
+        }
+        assert_eq!(DETAIL_FIELDS[app.details_field], "Content");
+    }
+
+    #[test]
+    fn test_open_source_needs_an_existing_file() {
+        let (dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] ERROR ## [core] missing.cpp:3 then render.cpp:12\n",
+            &Config::default(),
+        );
+        fs::write(dir.path().join("render.cpp"), "").unwrap();
+
+        app.perform(Action::OpenSource).unwrap();
+        // The editor itself may come with arguments of its own from $EDITOR
+        let command = app.pending_command.unwrap();
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(
+            args[args.len() - 2..],
+            [OsStr::new("+12"), dir.path().join("render.cpp").as_os_str()]
+        );
+    }
+
+    #[test]
+    fn test_mouse_capture_state_shows_in_header() {
+        let (_dir, mut app) = app_with("", &Config::default());
+        let header = |app: &mut App| {
+            render_text(app, 120, 24)
+                .chars()
+                .take(120)
+                .collect::<String>()
+        };
+        assert!(!header(&mut app).contains("Mouse off"));
+
+        app.perform(Action::ToggleMouseCapture).unwrap();
+        assert!(header(&mut app).contains("Mouse off"));
+        app.perform(Action::ToggleMouseCapture).unwrap();
+        assert!(app.mouse_capture);
+    }
+
+    #[test]
+    fn test_save_recall_and_delete_preset() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let presets_path = dir.path().join("presets.toml");
+        app.presets_path = Some(presets_path.clone());
+        let type_keys = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(KeyEvent::from(KeyCode::Char(c))).unwrap();
+            }
+        };
+
+        app.filter_input = "net".to_string();
+        app.hidden_levels.insert("WARN");
+        app.detail_level = 3;
+        app.perform(Action::SavePreset).unwrap();
+        type_keys(&mut app, "network");
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(Presets::load(&presets_path).unwrap().presets.len(), 1);
+
+        app.filter_input.clear();
+        app.hidden_levels.clear();
+        app.detail_level = 1;
+        app.apply_filter(false);
+        type_keys(&mut app, "1");
+        assert_eq!(app.filter_input, "net");
+        assert_eq!(app.detail_level, 3);
+        // "net" without WARN leaves the one INFO network log
+        assert_eq!(app.displaying_logs.items.len(), 1);
+
+        app.perform(Action::LoadPreset).unwrap();
+        type_keys(&mut app, "d");
+        app.handle_key(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert!(Presets::load(&presets_path).unwrap().presets.is_empty());
+        assert_eq!(app.preset_picker, None);
+    }
+
+    #[test]
+    fn test_group_by_tag_sections() {
+        let (dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] n1\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [ui] u1\n\
+             ## 2024-01-01 10:00:02 [main] INFO ## [net] n2\n",
+            &Config::default(),
+        );
+        let path = dir.path().join("preview.log");
+        // Top to bottom, as drawn
+        let rows = |app: &App| -> Vec<String> {
+            app.displaying_logs
+                .items
+                .iter()
+                .rev()
+                .map(|item| item.content.clone())
+                .collect()
+        };
+
+        app.perform(Action::ToggleTagGroups).unwrap();
+        assert_eq!(rows(&app), ["2 logs", "n2", "n1", "1 log", "u1"]);
+
+        // Collapsing from a member row folds its section and selects the header
+        app.displaying_logs.state.select(Some(2));
+        app.update_selected_uuid();
+        app.perform(Action::ToggleGroup).unwrap();
+        assert_eq!(rows(&app), ["2 logs", "1 log", "u1"]);
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+
+        // New logs land in their section and keep the header selected
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "## 2024-01-01 10:00:03 [main] INFO ## [ui] u2").unwrap();
+        drop(file);
+        app.update_logs().unwrap();
+        assert_eq!(rows(&app), ["2 logs", "u2", "u1", "2 logs"]);
+        assert_eq!(app.displaying_logs.state.selected(), Some(3));
+
+        app.perform(Action::ToggleTagGroups).unwrap();
+        assert_eq!(rows(&app), ["u2", "n2", "u1", "n1"]);
+        assert!(app.group_headers.is_empty());
+    }
+
+    #[test]
+    fn test_group_header_is_not_a_log() {
+        let (dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] n1 at src/main.rs:1\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [ui] u1\n",
+            &Config::default(),
+        );
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        app.copy_on_select = true;
+        app.perform(Action::ToggleTagGroups).unwrap();
+
+        // Top to bottom: the ui header, u1, the net header, n1
+        app.displaying_logs.state.select(Some(0));
+        app.update_selected_uuid();
+        app.arm_copy_on_select();
+        assert!(app.selected_log().is_none());
+        assert!(app.selected_items().is_empty());
+        assert_eq!(app.selected_source_location(), None);
+        assert_eq!(app.pending_copy, None);
+        let text = render_text(&mut app, 120, 30);
+        assert!(text.contains("Section of 1 log tagged [ui]"));
+        assert!(!text.contains("Select a log item"));
+
+        // A range over a header copies only the logs, oldest first
+        app.selection_anchor = app.selected_log_uuid;
+        app.displaying_logs.state.select(Some(1));
+        app.update_selected_uuid();
+        app.displaying_logs.state.select(Some(3));
+        app.update_selected_uuid();
+        let content = app.make_range_yank_content(&app.selected_items());
+        assert_eq!(
+            content,
+            "[2024-01-01 10:00:00] [main] INFO ## [net] n1 at src/main.rs:1\n\
+             [2024-01-01 10:00:01] [main] INFO ## [ui] u1"
+        );
+    }
+
+    #[test]
+    fn test_repeated_time_is_blanked() {
+        let config: Config = toml::from_str("repeated_time = \"blank\"\n").unwrap();
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] second\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] third\n",
+            &config,
+        );
+
+        let text = render_text(&mut app, 80, 24);
+        // "third" is drawn first and keeps its time; "second" is in the same second
+        assert_eq!(text.matches("[2024-01-01 10:00:01]").count(), 1);
+        assert_eq!(text.matches("[2024-01-01 10:00:00]").count(), 1);
+        assert!(text.contains(&format!("{} second", " ".repeat(21))));
+    }
+
+    #[test]
+    fn test_live_and_older_markers() {
+        let (_dir, mut app) = app_with(&numbered_lines(100), &Config::default());
+
+        let text = render_text(&mut app, 80, 40);
+        let older = 100 - list_height(&app);
+        assert!(text.contains("─── live ───"));
+        assert!(text.contains(&format!("─── {} older ───", older)));
+
+        // Scrolled away from the newest log there's no live edge on screen
+        app.perform(Action::SelectOldest).unwrap();
+        let text = render_text(&mut app, 80, 40);
+        assert!(!text.contains("─── live ───"));
+        assert!(!text.contains("older"));
+        assert!(text.contains("line 0"));
+    }
+
+    #[test]
+    fn test_json_content_is_pretty_printed() {
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] {\"user\":\"ann\",\"ids\":[1,2]}\n",
+            &Config::default(),
+        );
+        assert!(!render_text(&mut app, 100, 40).contains("\"user\": \"ann\","));
+
+        app.perform(Action::ToggleJson).unwrap();
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("  \"user\": \"ann\","));
+        assert!(text.contains("    1,"));
+    }
+
+    #[test]
+    fn test_wrap_toggle_cuts_multi_line_previews() {
+        let config: Config = toml::from_str("[preview]\nlines = 2\n").unwrap();
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first\nsecond\n",
+            &config,
+        );
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("first ⏎ second"));
+        assert!(text.contains("LOG DETAILS | Wrap"));
+
+        app.perform(Action::ToggleWrap).unwrap();
+        let text = render_text(&mut app, 100, 40);
+        assert!(!text.contains("⏎"));
+        assert!(text.contains("LOG DETAILS | No Wrap"));
+        assert!(text.contains("preview.log | No Wrap"));
+    }
+
+    #[test]
+    fn test_rotation_reads_the_rotated_tail_first() {
+        let (dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] one\n",
+            &Config::default(),
+        );
+        let live = dir.path().join("preview.log");
+
+        // Written just before the app rotates the file away and starts a fresh one
+        let mut file = fs::OpenOptions::new().append(true).open(&live).unwrap();
+        writeln!(file, "## 2024-01-01 10:00:01 [main] INFO ## [net] two").unwrap();
+        drop(file);
+        fs::rename(&live, dir.path().join("preview.1.log")).unwrap();
+        fs::write(&live, "## 2024-01-01 10:00:02 [main] INFO ## [net] three\n").unwrap();
+
+        app.update_logs().unwrap();
+        let contents: Vec<&str> = app
+            .raw_logs
+            .iter()
+            .map(|item| item.content.as_str())
+            .collect();
+        assert_eq!(contents, ["one", "two", "three"]);
+    }
+
+    #[test]
+    fn test_age_fade_dims_older_rows() {
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 09:00:00 [main] INFO ## [net] old\n\
+             ## 2024-01-01 10:00:00 [main] INFO ## [net] recent\n\
+             ## 2024-01-01 10:00:05 [main] INFO ## [net] newest\n",
+            &Config::default(),
+        );
+        app.perform(Action::ToggleAgeFade).unwrap();
+        app.displaying_logs.state.select(None);
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        app.render(area, &mut buf);
+        let info_fg = theme::INFO_STYLE.fg.unwrap();
+        assert_eq!(row_fg(&buf, "recent"), info_fg);
+        assert_ne!(row_fg(&buf, "old"), info_fg);
+    }
+
+    #[test]
+    fn test_tag_levels_color_rows() {
+        let config: Config = toml::from_str("[tag_levels]\ncrash = \"ERROR\"\n").unwrap();
+        let (_dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [Crash] boom\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] fine\n",
+            &config,
+        );
+        app.displaying_logs.state.select(None);
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        app.render(area, &mut buf);
+        assert_eq!(row_fg(&buf, "boom"), theme::ERROR_STYLE.fg.unwrap());
+        assert_eq!(row_fg(&buf, "fine"), theme::INFO_STYLE.fg.unwrap());
+    }
+
+    #[test]
+    fn test_header_title_is_configurable() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        assert!(render_text(&mut app, 100, 40).contains("Termlog | Autoscroll ON"));
+
+        let config: Config =
+            toml::from_str("title = \"Effect Preview\"\ntitle_shows_file = true\n").unwrap();
+        let (_dir, mut app) = app_with(LOG_LINES, &config);
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("Effect Preview | preview.log | Autoscroll ON"));
+    }
+
+    #[test]
+    fn test_footer_shows_file_size() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(3 * 1024 * 1024 * 1024 / 2), "1.5 GiB");
+
+        // A file switched to shows no size until it is read
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.switch_to_log_file(dir.path().join("preview.log"))
+            .unwrap();
+        assert_eq!(app.file_size_readout(), None);
+        app.update_logs().unwrap();
+        let size = human_size(LOG_LINES.len() as u64);
+        assert_eq!(app.file_size_readout(), Some(size.clone()));
+        assert!(render_text(&mut app, 100, 40).contains(&size));
+
+        // Partway through a large delta both the parsed and the total length show
+        app.last_len = 10;
+        app.load_progress = Some((10, 2048));
+        assert_eq!(app.file_size_readout(), Some("10 B / 2.0 KiB".to_string()));
+    }
+
+    #[test]
+    fn test_autoscroll_ignores_growth_hidden_by_filter() {
+        let config = Config {
+            autoscroll_mode: AutoscrollMode::Manual,
+            ..Config::default()
+        };
+        let (dir, mut app) = app_with(&numbered_lines(100), &config);
+        let log_path = dir.path().join("preview.log");
+        app.filter_input = "tag:net".to_string();
+        app.apply_filter(false);
+        render_text(&mut app, 80, 40);
+        app.handle_logs_view_scrolling(true).unwrap();
+        let scrolled = app.logs_block.get_scroll_position();
+        assert!(scrolled > 0);
+        assert!(app.autoscroll);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(file, "## 2024-01-01 10:00:01 [main] INFO ## [ui] hidden").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.logs_block.get_scroll_position(), scrolled);
+
+        writeln!(file, "## 2024-01-01 10:00:02 [main] INFO ## [net] shown").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.logs_block.get_scroll_position(), 0);
+    }
+
+    #[test]
+    fn test_selection_stops_at_ends_without_wrap_around() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.perform(Action::SelectPrevious).unwrap();
+        assert_eq!(
+            app.displaying_logs.state.selected(),
+            Some(app.displaying_logs.items.len() - 1)
+        );
+
+        let config = Config {
+            wrap_around: Some(false),
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(LOG_LINES, &config);
+        app.perform(Action::SelectPrevious).unwrap();
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+        app.perform(Action::SelectOldest).unwrap();
+        app.perform(Action::SelectNext).unwrap();
+        assert_eq!(
+            app.displaying_logs.state.selected(),
+            Some(app.displaying_logs.items.len() - 1)
+        );
+    }
+
+    #[test]
+    fn test_grep_file_jumps_to_nearest_log() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.perform(Action::GrepFile).unwrap();
+        app.handle_paste("DRAWN");
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        let grep = app.file_grep.as_ref().unwrap();
+        assert_eq!(grep.matches.len(), 1);
+        assert_eq!(grep.matches[0].line_number, 2);
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("FILE GREP | 'DRAWN' | 1 lines"));
+        assert!(text.contains("Line 2 at byte 54"));
+
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert!(app.file_grep.is_none());
+        assert_eq!(app.selected_log_uuid, Some(app.raw_logs[1].id));
+
+        app.perform(Action::GrepFile).unwrap();
+        app.handle_paste("missing");
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert!(app.file_grep.is_none());
+        assert!(app.toast.is_some());
+    }
+
+    #[test]
+    fn test_detail_level_default_and_bounds() {
+        let config = Config {
+            detail_level: Some(3),
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(LOG_LINES, &config);
+        assert_eq!(app.detail_level, 3);
+        for _ in 0..3 {
+            app.perform(Action::IncreaseDetail).unwrap();
+        }
+        assert_eq!(app.detail_level, MAX_DETAIL_LEVEL);
+
+        let config = Config {
+            detail_level: Some(9),
+            ..Config::default()
+        };
+        let (_dir, app) = app_with(LOG_LINES, &config);
+        assert_eq!(app.detail_level, MAX_DETAIL_LEVEL);
+    }
+
+    #[test]
+    fn test_yank_menu_sets_default_format() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.perform(Action::YankMenu).unwrap();
+        assert_eq!(app.yank_menu, Some(0));
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("YANK AS"));
+        assert!(text.contains("1 Markdown bundle  (y)"));
+
+        app.handle_key(KeyEvent::from(KeyCode::Char('3'))).unwrap();
+        assert_eq!(app.yank_menu, None);
+        assert_eq!(app.yank_format, YankFormat::KeyValue);
+        assert!(app.toast.is_some());
+
+        // Reopened, the menu starts on the format y now copies
+        app.perform(Action::YankMenu).unwrap();
+        assert_eq!(app.yank_menu, Some(2));
+        app.handle_key(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(app.yank_menu, None);
+        assert_eq!(app.selected_items().len(), 1);
+    }
+
+    #[test]
+    fn test_empty_content_placeholder_or_skip() {
+        let lines = format!("{}## 2024-01-01 10:00:03 [main] INFO ## [net]\n", LOG_LINES);
+        let (_dir, mut app) = app_with(&lines, &Config::default());
+        assert_eq!(app.displaying_logs.items.len(), 4);
+        assert!(render_text(&mut app, 100, 40).contains("<empty>"));
+
+        let config = Config {
+            empty_content: EmptyContent::Skip,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&lines, &config);
+        assert_eq!(app.raw_logs.len(), 4);
+        assert_eq!(app.displaying_logs.items.len(), 3);
+        assert!(!render_text(&mut app, 100, 40).contains("<empty>"));
+    }
+
+    #[test]
+    fn test_growth_continuing_last_log_updates_it() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+        let last_id = app.raw_logs[2].id;
+
+        // More lines of the last log, then half of a new one
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(
+            file,
+            "\tat Net.send(Net.java:42)\n## 2024-01-01 10:00:03 [main] INFO ## [net] ha"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 4);
+        assert_eq!(app.raw_logs[2].id, last_id);
+        assert_eq!(
+            app.raw_logs[2].content,
+            "retrying\n\tat Net.send(Net.java:42)"
+        );
+        assert_eq!(app.raw_logs[3].content, "ha");
+
+        writeln!(file, "lf done").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 4);
+        assert_eq!(app.raw_logs[3].content, "half done");
+        assert_eq!(app.raw_logs[3].index, 3);
+    }
+
+    #[test]
+    fn test_details_follow_selected_log_as_it_grows_and_shrinks() {
+        let (dir, mut app) = app_with(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] {\"user\":\"ann\"}\n",
+            &Config::default(),
+        );
+        let log_path = dir.path().join("preview.log");
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        app.perform(Action::ToggleJson).unwrap();
+        let last_id = app.raw_logs[1].id;
+        assert_eq!(app.selected_log_uuid, Some(last_id));
+        assert!(render_text(&mut app, 100, 40).contains("\"user\": \"ann\""));
+
+        // Half a header line reads as more of the selected log, which is no JSON then
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(file, "## 2024-01-01 10:00:0").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 2);
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("## 2024-01-01 10:00:0"));
+        assert!(!text.contains("\"user\": \"ann\""));
+
+        // Completed, the header starts a log of its own and the selected one shrinks back
+        writeln!(file, "2 [main] INFO ## [net] next").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs.len(), 3);
+        assert_eq!(app.selected_log_uuid, Some(last_id));
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("\"user\": \"ann\""));
+        assert!(!text.contains("## 2024-01-01 10:00:0"));
+    }
+
+    #[test]
+    fn test_log_growing_into_the_filter_shows_up() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+        app.filter_input = "timeout".to_string();
+        app.apply_filter(false);
+        assert!(app.displaying_logs.items.is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(file, "\tcaused by timeout").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(displayed_ids(&app), [app.raw_logs[2].id]);
+        assert_eq!(app.unread, 0);
+    }
+
+    #[test]
+    fn test_recording_keeps_growth_of_last_log() {
+        let (_record_dir, record_path) = temp_file("session.txt", "");
+        let config = Config {
+            record_path: Some(record_path.clone()),
+            ..Config::default()
+        };
+        let (dir, mut app) = app_with(LOG_LINES, &config);
+        let log_path = dir.path().join("preview.log");
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(
+            file,
+            "\tat Net.send(Net.java:42)\n## 2024-01-01 10:00:03 [main] INFO ## [net] ha"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        writeln!(file, "lf done").unwrap();
+        app.update_logs().unwrap();
+        drop(app);
+
+        assert_eq!(
+            fs::read_to_string(&record_path).unwrap(),
+            format!(
+                "{}\tat Net.send(Net.java:42)\n\
+                 ## 2024-01-01 10:00:03 [main] INFO ## [net] half done\n",
+                LOG_LINES
+            )
+        );
+    }
+
+    #[test]
+    fn test_unread_counter_while_paused() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(
+            file,
+            "## 2024-01-01 10:00:03 [main] INFO ## [net] while following"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.unread, 0);
+
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        writeln!(file, "## 2024-01-01 10:00:04 [main] INFO ## [net] one").unwrap();
+        writeln!(file, "## 2024-01-01 10:00:05 [main] INFO ## [ui] two").unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.unread, 2);
+        assert!(render_text(&mut app, 120, 40).contains("↑ 2 new"));
+
+        app.perform(Action::SelectNewest).unwrap();
+        assert_eq!(app.unread, 0);
+        assert!(!render_text(&mut app, 120, 40).contains(" new"));
+    }
+
+    #[test]
+    fn test_go_live_resumes_following_in_manual_mode() {
+        let config = Config {
+            autoscroll_mode: AutoscrollMode::Manual,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&numbered_lines(100), &config);
+        render_text(&mut app, 80, 40);
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        app.perform(Action::SelectOldest).unwrap();
+        app.unread = 7;
+        assert!(app.logs_block.get_scroll_position() > 0);
+
+        // In manual mode g alone doesn't resume following
+        app.perform(Action::SelectNewest).unwrap();
+        assert!(!app.autoscroll);
+
+        app.perform(Action::SelectOldest).unwrap();
+        app.unread = 7;
+        app.perform(Action::GoLive).unwrap();
+        assert!(app.autoscroll);
+        assert_eq!(app.unread, 0);
+        assert_eq!(app.logs_block.get_scroll_position(), 0);
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_debug_logs_have_their_own_filter() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.debug_logs.lock().unwrap().extend([
+            DebugRecord::new(log::Level::Info, "termlog", "watcher started"),
+            DebugRecord::new(log::Level::Warn, "termlog", "poll slow"),
+            DebugRecord::new(log::Level::Info, "termlog::file_grep", "grep done"),
+        ]);
+
+        app.set_focused_block(app.debug_block.id());
+        app.perform(Action::Filter).unwrap();
+        for c in "POLL".chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(app.debug_filter, "POLL");
+        // LOGS keeps showing everything
+        assert!(app.filter_input.is_empty());
+        assert_eq!(displayed_ids(&app).len(), 3);
+        let text = render_text(&mut app, 120, 40);
+        assert!(text.contains("DEBUG LOGS | Filter: POLL"));
+        assert!(text.contains("poll slow"));
+        assert!(!text.contains("watcher started"));
+        app.debug_filter = "file_grep".to_string();
+        assert!(render_text(&mut app, 120, 40).contains("grep done"));
+        app.debug_filter = "POLL".to_string();
+
+        // Cancelling an edit puts the DEBUG filter back as it was
+        app.perform(Action::Filter).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Char('x'))).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(app.debug_filter, "POLL");
+
+        app.set_focused_block(app.logs_block.id());
+        app.perform(Action::Filter).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Char('u'))).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Char('i'))).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(app.filter_input, "ui");
+        assert_eq!(displayed_ids(&app).len(), 1);
+        assert_eq!(app.debug_filter, "POLL");
+    }
+
+    #[test]
+    fn test_sequence_numbers_keep_parse_order() {
+        let config = Config {
+            show_sequence: true,
+            ..Config::default()
+        };
+        let (dir, mut app) = app_with(LOG_LINES, &config);
+        let log_path = dir.path().join("preview.log");
+        let seqs = |app: &App| app.raw_logs.iter().map(|item| item.seq).collect::<Vec<_>>();
+        assert_eq!(seqs(&app), [0, 1, 2]);
+
+        // The re-parsed last log keeps its number
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(
+            file,
+            "\tat Net.send(Net.java:42)\n## 2024-01-01 10:00:02 [main] INFO ## [ui] same second"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(seqs(&app), [0, 1, 2, 3]);
+
+        // Clearing restarts positions but not the sequence
+        app.perform(Action::ClearLogs).unwrap();
+        writeln!(
+            file,
+            "## 2024-01-01 10:00:04 [main] INFO ## [net] after clear"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+        assert_eq!(app.raw_logs[0].index, 0);
+        assert_eq!(seqs(&app), [4]);
+        assert!(render_text(&mut app, 120, 40).contains("#4 "));
+    }
+
+    #[test]
+    fn test_hud_shows_counts_and_frame_time() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        assert!(!render_text(&mut app, 120, 40).contains("HUD"));
+
+        app.perform(Action::ToggleHud).unwrap();
+        app.filter_input = "net".to_string();
+        app.apply_filter(false);
+        app.last_frame_time = Some(Duration::from_micros(2500));
+        let text = render_text(&mut app, 120, 40);
+        assert!(text.contains("parsed  3"));
+        assert!(text.contains("shown   2"));
+        assert!(text.contains("memory  ~"));
+        assert!(text.contains("frame   2.5 ms"));
+    }
+
+    #[test]
+    fn test_details_highlight_filter_matches() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let highlighted = |app: &mut App| {
+            let area = Rect::new(0, 0, 120, 40);
+            let mut buf = Buffer::empty(area);
+            app.render(area, &mut buf);
+            buf.content()
+                .iter()
+                .filter(|cell| Some(cell.bg) == theme::MATCH_STYLE.bg)
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        app.filter_input = "RETRY OR net".to_string();
+        app.apply_filter(false);
+        assert_eq!(highlighted(&mut app), "retry");
+
+        app.filter_input.clear();
+        app.apply_filter(false);
+        assert_eq!(highlighted(&mut app), "");
+    }
+
+    #[test]
+    fn test_toggle_filter_keeps_filter_and_selection() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        app.filter_input = "net".to_string();
+        app.apply_filter(false);
+        // The oldest "net" log: net connected
+        app.perform(Action::SelectOldest).unwrap();
+        let selected = app.selected_log_uuid;
+        assert_eq!(selected, Some(app.raw_logs[0].id));
+
+        app.perform(Action::ToggleFilter).unwrap();
+        assert_eq!(app.filter_input, "net");
+        assert_eq!(displayed_ids(&app).len(), 3);
+        assert_eq!(app.selected_log_uuid, selected);
+        assert!(render_text(&mut app, 120, 40).contains("| Filter Off"));
+
+        app.perform(Action::ToggleFilter).unwrap();
+        assert_eq!(displayed_ids(&app), filtered_raw_ids(&app));
+        assert_eq!(app.selected_log_uuid, selected);
+        assert!(!render_text(&mut app, 120, 40).contains("Filter Off"));
+
+        // Opening the filter input turns it back on, even when cancelled
+        app.perform(Action::ToggleFilter).unwrap();
+        app.perform(Action::Filter).unwrap();
+        app.handle_key(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(app.filter_input, "net");
+        assert_eq!(displayed_ids(&app).len(), 2);
+    }
+
+    #[test]
+    fn test_copy_on_select_waits_for_the_selection_to_settle() {
+        let config = Config {
+            copy_on_select: true,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(LOG_LINES, &config);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Selecting the newest log on load isn't a move
+        assert!(app.selected_log_uuid.is_some());
+        assert_eq!(app.settled_copy(at(1000)), None);
+
+        // Moving on before it settles copies nothing
+        app.perform(Action::SelectNext).unwrap();
+        assert_eq!(app.settled_copy(at(0)), None);
+        app.perform(Action::SelectNext).unwrap();
+        assert_eq!(app.settled_copy(at(100)), None);
+        assert_eq!(app.settled_copy(at(300)), None);
+        assert_eq!(
+            app.settled_copy(at(400)).as_deref(),
+            Some("[main] INFO ## [net] connected")
+        );
+        // Only once per selection
+        assert_eq!(app.settled_copy(at(800)), None);
+        assert_eq!(app.settled_copy(at(1200)), None);
+
+        app.copy_on_select = false;
+        app.perform(Action::SelectPrevious).unwrap();
+        assert_eq!(app.settled_copy(at(1300)), None);
+        assert_eq!(app.settled_copy(at(2000)), None);
+    }
+
+    #[test]
+    fn test_sticky_time_shows_minute_of_top_log() {
+        let lines: String = (0..30)
+            .map(|i| {
+                format!(
+                    "## 2024-01-01 10:{:02}:00 [main] INFO ## [net] log {}\n",
+                    i, i
+                )
+            })
+            .collect();
+        let config = Config {
+            sticky_time: true,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&lines, &config);
+
+        // Following at the top the live marker keeps its place
+        let text = render_text(&mut app, 80, 30);
+        assert!(text.contains("─── live ───"));
+        assert!(!text.contains("─── 2024"));
+
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        app.logs_block.set_scroll_position(10);
+        let text = render_text(&mut app, 80, 30);
+        assert!(text.contains("─── 2024-01-01 10:19 ───"), "{}", text);
+        // The row is taken from the list, not drawn over a log
+        assert!(text.contains("log 19"));
+    }
+
+    #[test]
+    fn test_inspect_parse_in_details() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.perform(Action::ToggleInspectParse).unwrap();
+        assert!(!app.details_inspect);
+
+        app.set_focused_block(app.details_block.id());
+        app.perform(Action::ToggleHexDump).unwrap();
+        app.perform(Action::ToggleInspectParse).unwrap();
+        assert!(app.details_inspect && !app.details_hex);
+        let text = render_text(&mut app, 120, 60);
+        assert!(text.contains("Parse steps:"));
+        assert!(text.contains(r#"[origin] LEVEL ## [TAG]: origin="main" level="WARN" tag="net""#));
+        assert!(text.contains("no matcher claims it"));
+
+        app.perform(Action::ToggleInspectParse).unwrap();
+        assert!(!render_text(&mut app, 120, 60).contains("Parse steps:"));
+    }
+
+    #[test]
+    fn test_command_palette_scrolls_to_selection() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.perform(Action::CommandPalette).unwrap();
+        assert!(Action::PALETTE.len() > 20);
+        for _ in 0..Action::PALETTE.len() {
+            app.handle_key(KeyEvent::from(KeyCode::Down)).unwrap();
+        }
+        let last = Action::PALETTE.last().unwrap();
+        let text = render_text(&mut app, 80, 20);
+        assert!(text.contains(last.name()));
+        assert!(!text.contains(Action::PALETTE[0].name()));
+    }
+
+    #[test]
+    fn test_help_lists_every_key() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        assert!(render_text(&mut app, 120, 30).contains("?: keys"));
+
+        app.handle_key(KeyEvent::from(KeyCode::Char('?'))).unwrap();
+        let text = render_text(&mut app, 120, 30);
+        assert!(text.contains("KEYS"));
+        assert!(text.contains("Move the selection"));
+        // Moving down the list scrolls to the keys that didn't fit
+        for _ in 0..action::KEY_HELP.len() {
+            app.handle_key(KeyEvent::from(KeyCode::Char('j'))).unwrap();
+        }
+        let text = render_text(&mut app, 120, 30);
+        assert!(text.contains("Quit"));
+        assert!(!text.contains("Move the selection"));
+
+        app.handle_key(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert!(app.help.is_none());
+        assert!(!render_text(&mut app, 120, 30).contains("KEYS"));
+    }
+
+    #[test]
+    fn test_legend_click_toggles_level() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let header: String = render_text(&mut app, 80, 24).chars().take(80).collect();
+        assert!(header.ends_with("[E][W][I][D] "));
+
+        // Click the [I] cell: the two INFO logs disappear
+        let click = |column| MouseEvent {
+            kind: MouseEventKind::Up(crossterm::event::MouseButton::Left),
+            column,
+            row: 0,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+        app.event = Some(click(80 - 13 + 6));
+        render_text(&mut app, 80, 24);
+        assert_eq!(app.displaying_logs.items.len(), 1);
+        assert_eq!(app.displaying_logs.items[0].level, "WARN");
+
+        app.event = Some(click(80 - 13 + 7));
+        render_text(&mut app, 80, 24);
+        assert_eq!(app.displaying_logs.items.len(), 3);
+    }
+
+    #[test]
+    fn test_diff_with_previous_selection() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+        render_text(&mut app, 100, 40);
+
+        app.perform(Action::ToggleDiff).unwrap();
+        // Nothing to compare against until the selection moves
+        assert!(!render_text(&mut app, 100, 40).contains("diff with previous"));
+
+        // newest "retrying" (WARN) -> "drawn" (INFO)
+        app.perform(Action::SelectNext).unwrap();
+        let text = render_text(&mut app, 100, 40);
+        assert!(text.contains("Content (diff with previous selection):"));
+        assert!(text.contains("INFO (was WARN)"));
+        assert!(text.contains("- retrying"));
+        assert!(text.contains("+ drawn"));
+    }
+
+    #[test]
+    fn test_paste_into_filter_is_one_line() {
+        let (_dir, mut app) = app_with(LOG_LINES, &Config::default());
+
+        app.handle_paste("ignored");
+        assert!(app.filter_input.is_empty());
+
+        app.perform(Action::Filter).unwrap();
+        app.handle_paste("tag:net\r\nretry\n");
+        assert_eq!(app.filter_input, "tag:net retry");
+        assert_eq!(app.displaying_logs.items.len(), 1);
+        app.handle_key(KeyEvent::from(KeyCode::Enter)).unwrap();
+
+        app.perform(Action::CommandPalette).unwrap();
+        app.handle_paste("yank");
+        assert_eq!(app.palette_input, "yank");
+        assert!(app.filter_input.ends_with("retry"));
+    }
+
+    #[test]
+    fn test_reload_rereads_file_and_keeps_filter() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+        app.filter_input = "net".to_string();
+        app.apply_filter(false);
+        app.perform(Action::SelectOldest).unwrap();
+
+        // Rewritten in place with the same length: the tailer can't tell anything changed
+        let rewritten = LOG_LINES.replace("connected", "CONNECTED");
+        fs::write(&log_path, rewritten).unwrap();
+        app.perform(Action::Reload).unwrap();
+
+        assert_eq!(app.raw_logs.len(), 3);
+        assert_eq!(app.raw_logs[0].content, "CONNECTED");
+        assert_eq!(displayed_ids(&app), filtered_raw_ids(&app));
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_manual_autoscroll_ignores_scrolling() {
+        let config = Config {
+            autoscroll_mode: AutoscrollMode::Manual,
+            ..Config::default()
+        };
+        let (_dir, mut app) = app_with(&numbered_lines(100), &config);
+        render_text(&mut app, 80, 40);
+
+        app.handle_logs_view_scrolling(true).unwrap();
+        assert!(app.logs_block.get_scroll_position() > 0);
+        assert!(app.autoscroll);
+
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        assert!(!app.autoscroll);
+        app.perform(Action::SelectNewest).unwrap();
+        assert!(!app.autoscroll);
+    }
+
+    #[test]
+    fn test_autoscroll_key_resumes_at_newest() {
+        let (_dir, mut app) = app_with(&numbered_lines(100), &Config::default());
+        render_text(&mut app, 80, 40);
+
+        // Turning it off at the top sticks across frames
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        assert!(render_text(&mut app, 80, 40).contains("Autoscroll paused"));
+        assert!(!app.autoscroll);
+
+        app.perform(Action::SelectOldest).unwrap();
+        app.perform(Action::ToggleAutoscroll).unwrap();
+        assert!(app.autoscroll);
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+        assert_eq!(app.logs_block.get_scroll_position(), 0);
+        assert!(render_text(&mut app, 80, 40).contains("Autoscroll ON"));
+    }
+
+    #[test]
+    fn test_autoscroll_follows_growth_without_selection() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+
+        // Select the "ui" log, then filter it away: nothing is selected any more
+        app.perform(Action::SelectNext).unwrap();
+        app.filter_input = "net".to_string();
+        app.apply_filter(false);
+        assert_eq!(app.displaying_logs.state.selected(), None);
+        assert!(app.autoscroll);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(file, "## 2024-01-01 10:00:03 [main] INFO ## [net] newest").unwrap();
+        app.update_logs().unwrap();
+
+        assert_eq!(app.logs_block.get_scroll_position(), 0);
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+        let newest = app.displaying_logs.items.last().unwrap();
+        assert_eq!(newest.content, "newest");
+        assert_eq!(app.selected_log_uuid, Some(newest.id));
+        assert!(render_text(&mut app, 80, 24).contains("newest"));
+    }
+
+    #[test]
+    fn test_growth_after_clear_selects_newest() {
+        let (dir, mut app) = app_with(LOG_LINES, &Config::default());
+        let log_path = dir.path().join("preview.log");
+        app.perform(Action::ClearLogs).unwrap();
+
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        writeln!(
+            file,
+            "## 2024-01-01 10:00:03 [main] INFO ## [net] after clear"
+        )
+        .unwrap();
+        app.update_logs().unwrap();
+
+        assert_eq!(app.displaying_logs.items.len(), 1);
+        assert_eq!(app.displaying_logs.state.selected(), Some(0));
+    }
+}
@@ -1,4 +1,4 @@
-use crossterm::event::{MouseEvent, MouseEventKind};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::Rect,
     prelude::Stylize,
@@ -16,8 +16,14 @@ pub struct AppBlock {
     title: Option<String>,
     lines_count: usize,
     scroll_position: usize,
+    viewport_height: usize, // Rows of content in view; 0 until set, which hides the percentage
+    content_width: usize,
+    h_scroll_position: usize,
     scrollbar_state: ScrollbarState,
     padding: Option<Padding>,
+    borders: Borders,
+    border_type: BorderType,
+    focus_follows_mouse: bool,
 }
 
 impl AppBlock {
@@ -27,8 +33,14 @@ impl AppBlock {
             title: None,
             lines_count: 0,
             scroll_position: 0,
+            viewport_height: 0,
+            content_width: 0,
+            h_scroll_position: 0,
             scrollbar_state: ScrollbarState::default(),
             padding: None,
+            borders: Borders::TOP | Borders::LEFT,
+            border_type: BorderType::Rounded,
+            focus_follows_mouse: true,
         }
     }
 
@@ -42,6 +54,18 @@ impl AppBlock {
         self
     }
 
+    pub fn set_borders(mut self, borders: Borders, border_type: BorderType) -> Self {
+        self.borders = borders;
+        self.border_type = border_type;
+        self
+    }
+
+    /// Whether hovering the block reports it as focused, or only clicks do
+    pub fn set_focus_follows_mouse(mut self, focus_follows_mouse: bool) -> Self {
+        self.focus_follows_mouse = focus_follows_mouse;
+        self
+    }
+
     pub fn update_title(&mut self, title: impl Into<String>) {
         self.title = Some(title.into());
     }
@@ -53,8 +77,8 @@ impl AppBlock {
 
     pub fn build(&self, focused: bool) -> Block<'_> {
         let mut block = Block::default()
-            .borders(Borders::TOP | Borders::LEFT)
-            .border_type(BorderType::Rounded);
+            .borders(self.borders)
+            .border_type(self.border_type);
 
         if focused {
             block =
@@ -70,8 +94,12 @@ impl AppBlock {
             } else {
                 Style::new()
             };
+            let title = match self.scroll_indicator() {
+                Some(indicator) => format!("{} ({})", title, indicator),
+                None => title.clone(),
+            };
             block = block.title(
-                ratatui::prelude::Line::from(title.as_str())
+                ratatui::prelude::Line::from(title)
                     .style(title_style)
                     .centered(),
             );
@@ -105,6 +133,30 @@ impl AppBlock {
         self.lines_count
     }
 
+    /// Rows of content the block shows at once, for the scroll percentage in its title
+    pub fn set_viewport_height(&mut self, viewport_height: usize) {
+        self.viewport_height = viewport_height;
+    }
+
+    /// How far through its lines the block is scrolled, like vim's ruler: "ALL" when they
+    /// all fit, "TOP"/"BOT" at the ends and a percentage in between
+    pub fn scroll_indicator(&self) -> Option<String> {
+        if self.viewport_height == 0 {
+            return None;
+        }
+        let max_scroll = self.lines_count.saturating_sub(self.viewport_height);
+        let indicator = if max_scroll == 0 {
+            "ALL".to_string()
+        } else if self.scroll_position == 0 {
+            "TOP".to_string()
+        } else if self.scroll_position >= max_scroll {
+            "BOT".to_string()
+        } else {
+            format!("{}%", self.scroll_position * 100 / max_scroll)
+        };
+        Some(indicator)
+    }
+
     pub fn set_scroll_position(&mut self, scroll_position: usize) {
         self.scroll_position = scroll_position;
     }
@@ -113,22 +165,44 @@ impl AppBlock {
         self.scroll_position
     }
 
+    /// Width of the widest unwrapped line, used to clamp horizontal scrolling
+    pub fn set_content_width(&mut self, content_width: usize) {
+        self.content_width = content_width;
+    }
+
+    pub fn get_content_width(&self) -> usize {
+        self.content_width
+    }
+
+    pub fn set_h_scroll_position(&mut self, h_scroll_position: usize) {
+        self.h_scroll_position = h_scroll_position;
+    }
+
+    pub fn get_h_scroll_position(&self) -> usize {
+        self.h_scroll_position
+    }
+
     pub fn get_scrollbar_state(&mut self) -> &mut ScrollbarState {
         &mut self.scrollbar_state
     }
 
-    /// Creates a uniform scrollbar widget with consistent styling
-    pub fn create_scrollbar(focused: bool) -> Scrollbar<'static> {
+    /// Creates a uniform scrollbar widget with consistent styling. It starts with the top
+    /// right corner of the block's frame, if the frame has a top.
+    pub fn create_scrollbar(&self, focused: bool) -> Scrollbar<'static> {
         let color = if focused {
             palette::tailwind::ZINC.c100
         } else {
             palette::tailwind::ZINC.c600
         };
+        let corner = match self.border_type {
+            BorderType::Rounded => "╮",
+            _ => "┐",
+        };
 
         Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .symbols(scrollbar::VERTICAL)
             .style(Style::default().fg(color))
-            .begin_symbol(Some("╮"))
+            .begin_symbol(self.borders.contains(Borders::TOP).then_some(corner))
             .end_symbol(None)
             .track_symbol(Some("│"))
     }
@@ -138,25 +212,19 @@ impl AppBlock {
         self.build(focused).inner(area)
     }
 
-    pub fn handle_mouse_event(
-        &self,
-        _event: &MouseEvent,
-        area: Rect,
-        mouse_event: Option<&MouseEvent>,
-    ) -> bool {
-        if let Some(mouse_event) = mouse_event {
-            let inner_area = self.build(false).inner(area);
-            let is_hovering = inner_area.contains(ratatui::layout::Position::new(
-                mouse_event.column,
-                mouse_event.row,
-            ));
-
-            // Handle hover focus - return true if mouse is hovering over this block
-            if is_hovering && mouse_event.kind == MouseEventKind::Moved {
-                return true;
-            }
+    /// Whether `event` should focus this block: a left click inside it, or with
+    /// focus-follows-mouse on, just hovering it
+    pub fn handle_mouse_event(&self, event: &MouseEvent, area: Rect, focused: bool) -> bool {
+        let inner_area = self.get_content_rect(area, focused);
+        if !inner_area.contains(ratatui::layout::Position::new(event.column, event.row)) {
+            return false;
+        }
+
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Up(MouseButton::Left) => true,
+            MouseEventKind::Moved => self.focus_follows_mouse,
+            _ => false,
         }
-        false
     }
 }
 
@@ -165,3 +233,25 @@ impl Default for AppBlock {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_indicator() {
+        let mut block = AppBlock::new();
+        assert_eq!(block.scroll_indicator(), None);
+
+        block.set_viewport_height(10);
+        block.set_lines_count(8);
+        assert_eq!(block.scroll_indicator().as_deref(), Some("ALL"));
+
+        block.set_lines_count(110);
+        assert_eq!(block.scroll_indicator().as_deref(), Some("TOP"));
+        block.set_scroll_position(42);
+        assert_eq!(block.scroll_indicator().as_deref(), Some("42%"));
+        block.set_scroll_position(100);
+        assert_eq!(block.scroll_indicator().as_deref(), Some("BOT"));
+    }
+}
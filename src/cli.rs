@@ -0,0 +1,86 @@
+use crate::{
+    config::Config,
+    log_parser::{LogFormat, MAX_DETAIL_LEVEL},
+    recorder::RecordFormat,
+};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// A fast, minimal, terminal-based log inspector.
+#[derive(Parser, Debug)]
+#[command(name = "termlog", version, about)]
+pub struct Cli {
+    /// Log file to open instead of following the newest live log. `.gz` files are
+    /// decompressed and shown without tailing
+    pub path: Option<PathBuf>,
+
+    /// Timestamp layout used to split the log into items [default: douyin]
+    #[arg(long, value_enum)]
+    pub format: Option<LogFormat>,
+
+    /// Append every parsed log item of this session to a file
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// How `--record` writes each item [default: raw]
+    #[arg(long, value_enum)]
+    pub record_format: Option<RecordFormat>,
+
+    /// Also append termlog's own log output, as seen in DEBUG LOGS, to a file
+    #[arg(long, value_name = "FILE")]
+    pub debug_log: Option<PathBuf>,
+
+    /// Fields shown in LOGS at startup, from 0 (content only) to 4 (time, level, origin and
+    /// tag) [default: 1]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=MAX_DETAIL_LEVEL as i64))]
+    pub detail_level: Option<u8>,
+
+    /// Text leading the header line [default: Termlog]
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Show the log file's name after the title in the header
+    #[arg(long)]
+    pub title_shows_file: bool,
+
+    /// Print the last parsed log items to stdout and exit instead of opening the TUI
+    #[arg(long)]
+    pub dump: bool,
+
+    /// Number of log items printed by `--dump`
+    #[arg(long, value_name = "N", default_value_t = 10, requires = "dump")]
+    pub lines: usize,
+}
+
+impl Cli {
+    /// Parses the process arguments. `--help`/`--version` (and invalid flags) print and exit
+    /// here, so callers must do this before touching the terminal.
+    pub fn parse_args() -> Self {
+        Self::parse()
+    }
+
+    /// Overrides the loaded config with any flags given on the command line
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(format) = self.format {
+            config.format = format;
+        }
+        if let Some(path) = &self.record {
+            config.record_path = Some(path.clone());
+        }
+        if let Some(record_format) = self.record_format {
+            config.record_format = record_format;
+        }
+        if let Some(path) = &self.debug_log {
+            config.debug_log_path = Some(path.clone());
+        }
+        if let Some(detail_level) = self.detail_level {
+            config.detail_level = Some(detail_level);
+        }
+        if let Some(title) = &self.title {
+            config.title = Some(title.clone());
+        }
+        if self.title_shows_file {
+            config.title_shows_file = true;
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{
+    log_parser::{LogFormat, PreviewOptions},
+    recorder::RecordFormat,
+    theme,
+};
+use anyhow::{Context, Result, anyhow};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::{BorderType, Borders},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Settings read from `<config dir>/termlog/config.toml`. Every field is optional in the
+/// file; command-line flags override whatever is loaded here.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub format: LogFormat,
+    /// Leads the header line, e.g. the name of the app being debugged [default: "Termlog"]
+    pub title: Option<String>,
+    /// Follow the title with the name of the log file being shown
+    pub title_shows_file: bool,
+    /// Regex matched against file names to find the live log, for apps whose rotated files
+    /// don't look like `name.1.log`
+    pub live_log_pattern: Option<String>,
+    /// File that every parsed log item is appended to while termlog runs
+    pub record_path: Option<PathBuf>,
+    pub record_format: RecordFormat,
+    /// File that termlog's own log output is appended to, all of it rather than the last
+    /// entries DEBUG LOGS keeps
+    pub debug_log_path: Option<PathBuf>,
+    /// Order the LOGS list by parsed timestamp instead of position in the file
+    pub sort_by_time: bool,
+    /// Lead each LOGS row with `#N`, the order termlog parsed it in, to tell apart logs
+    /// that share a timestamp
+    pub show_sequence: bool,
+    pub autoscroll_mode: AutoscrollMode,
+    /// Count the logs that arrive while autoscroll is paused in the LOGS title, as `↑ N new`
+    /// [default: true]
+    pub show_unread: Option<bool>,
+    /// Longest wait, in milliseconds, between checks of a log file that has stopped
+    /// changing [default: 2000]. Checks return to every 100 ms as soon as it changes.
+    pub max_poll_interval_ms: Option<u64>,
+    /// Re-center the LOGS list on the selection whenever it comes within `center_margin`
+    /// rows of the top or bottom, instead of scrolling just enough to keep it visible
+    pub center_selection: bool,
+    pub center_margin: usize,
+    /// Runs of more than this many identical logs in a row are folded into one `xN` row
+    /// [default: 5]. 0 never folds.
+    pub fold_threshold: Option<u32>,
+    /// Fields shown in LOGS at startup, from 0 (content only) to 4 (time, level, origin and
+    /// tag) [default: 1]
+    pub detail_level: Option<u8>,
+    /// Rows of context kept above and below the selection when moving it [default: 0]
+    pub scrolloff: Option<usize>,
+    /// Moving the selection past either end of LOGS continues from the other end
+    /// [default: true]
+    pub wrap_around: Option<bool>,
+    /// Copy the raw text of each log the selection is moved to, by keys or a click, to the
+    /// clipboard once the selection has stayed on it for a moment
+    pub copy_on_select: bool,
+    pub selection: SelectionConfig,
+    pub preview: PreviewOptions,
+    /// Wrap long lines in LOG DETAILS and show multi-line previews in LOGS [default: true].
+    /// The wrap key saves its choice here.
+    pub wrap: Option<bool>,
+    /// Show JSON contents indented in LOG DETAILS; `F` toggles it
+    pub pretty_json: bool,
+    pub repeated_time: RepeatedTime,
+    pub empty_content: EmptyContent,
+    /// `[tag_levels]`: tags whose LOGS rows are colored as a level of their own choosing,
+    /// e.g. `crash = "ERROR"`. Tags match ignoring case, and a tag's color beats the color
+    /// of the log's own level.
+    pub tag_levels: HashMap<String, String>,
+    /// Dim LOGS rows the older they are than the newest log
+    pub age_fade: bool,
+    /// Keep a row at the top of LOGS with the minute of the topmost log in view, whenever the
+    /// live marker isn't there
+    pub sticky_time: bool,
+    pub borders: BorderConfig,
+    /// Focus whichever panel the mouse is over; when off, only clicks and Tab move focus
+    /// [default: true]
+    pub focus_follows_mouse: Option<bool>,
+    /// Start each DEBUG LOGS entry with the local time it was logged, as `HH:MM:SS.mmm`
+    /// [default: true]
+    pub debug_timestamps: Option<bool>,
+}
+
+/// What turns following new logs on and off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoscrollMode {
+    /// Follow whenever the LOGS list is scrolled to the newest item, pause otherwise
+    #[default]
+    Auto,
+    /// Only the autoscroll key toggles it; scrolling around doesn't pause it, like `tail -f`
+    Manual,
+}
+
+/// How the LOGS list draws a time in the same second as the row above it, like `logcat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatedTime {
+    #[default]
+    Show,
+    Dim,
+    /// Leave the column empty, so only changes of second stand out
+    Blank,
+}
+
+/// How LOGS shows logs whose content is empty, e.g. header-only lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyContent {
+    /// Preview them as `<empty>`
+    #[default]
+    Placeholder,
+    /// Leave them out of the list
+    Skip,
+}
+
+/// `[selection]`: how the selected row of the LOGS list is marked. Colors are names like
+/// `blue` or hex like `#334155`; unset values keep the theme's.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SelectionConfig {
+    /// Drawn before the selected row [default: ">"]. Set to "" to highlight the row alone,
+    /// without indenting every row.
+    pub indicator: Option<String>,
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: Option<bool>,
+}
+
+impl SelectionConfig {
+    pub fn indicator(&self) -> &str {
+        self.indicator.as_deref().unwrap_or(">")
+    }
+
+    pub fn style(&self) -> Result<Style> {
+        let mut style = theme::SELECTED_STYLE;
+        if let Some(fg) = &self.fg {
+            style = style.fg(parse_color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(parse_color(bg)?);
+        }
+        match self.bold {
+            Some(true) => style = style.add_modifier(Modifier::BOLD),
+            Some(false) => style = style.remove_modifier(Modifier::BOLD),
+            None => {}
+        }
+        Ok(style)
+    }
+}
+
+/// `[borders]`: the frame drawn around the LOGS, LOG DETAILS and DEBUG LOGS panels
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BorderConfig {
+    pub sides: BorderSides,
+    /// Also used by the popups, which are always fully framed
+    pub corners: BorderCorners,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderSides {
+    /// Top and left, so neighbouring panels share their lines
+    #[default]
+    Partial,
+    All,
+    /// Only the titles, for the most room
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderCorners {
+    #[default]
+    Rounded,
+    Square,
+}
+
+impl BorderConfig {
+    pub fn borders(&self) -> Borders {
+        match self.sides {
+            BorderSides::Partial => Borders::TOP | Borders::LEFT,
+            BorderSides::All => Borders::ALL,
+            BorderSides::None => Borders::NONE,
+        }
+    }
+
+    pub fn border_type(&self) -> BorderType {
+        match self.corners {
+            BorderCorners::Rounded => BorderType::Rounded,
+            BorderCorners::Square => BorderType::Plain,
+        }
+    }
+}
+
+fn parse_color(color: &str) -> Result<Color> {
+    Color::from_str(color).map_err(|_| anyhow!("Invalid color '{}' in [selection]", color))
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("termlog").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to defaults when it doesn't exist
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config '{}'", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid config '{}'", path.display()))
+    }
+
+    /// Sets the top-level `wrap` key in the config file at `path`, leaving the rest of the
+    /// hand-edited file as it was
+    pub fn save_wrap(path: &Path, wrap: bool) -> Result<()> {
+        let text = if path.exists() {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config '{}'", path.display()))?
+        } else {
+            String::new()
+        };
+        let setting = format!("wrap = {}", wrap);
+
+        // Top-level keys end at the first table header
+        let mut lines: Vec<&str> = text.lines().collect();
+        let top_level = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .unwrap_or(lines.len());
+        let existing = lines[..top_level].iter().position(|line| {
+            line.split_once('=')
+                .is_some_and(|(key, _)| key.trim() == "wrap")
+        });
+        match existing {
+            Some(i) => lines[i] = &setting,
+            None => lines.insert(0, &setting),
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, lines.join("\n") + "\n")
+            .with_context(|| format!("Failed to write config '{}'", path.display()))
+    }
+
+    /// `tag_levels` keyed by lowercase tag, with the levels checked against the theme's
+    pub fn tag_levels(&self) -> Result<HashMap<String, String>> {
+        self.tag_levels
+            .iter()
+            .map(|(tag, level)| {
+                let level = level.to_uppercase();
+                if !theme::LEVELS.contains(&level.as_str()) {
+                    return Err(anyhow!(
+                        "Invalid level '{}' for tag '{}' in [tag_levels]",
+                        level,
+                        tag
+                    ));
+                }
+                Ok((tag.to_lowercase(), level))
+            })
+            .collect()
+    }
+
+    pub fn live_log_regex(&self) -> Result<Option<Regex>> {
+        self.live_log_pattern
+            .as_deref()
+            .map(|pattern| Regex::new(pattern).context("Invalid live_log_pattern"))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_config() {
+        let config: Config =
+            toml::from_str("[selection]\nindicator = \"\"\nbg = \"#334155\"\nbold = false\n")
+                .unwrap();
+        assert_eq!(config.selection.indicator(), "");
+        let style = config.selection.style().unwrap();
+        assert_eq!(style.bg, Some(Color::Rgb(0x33, 0x41, 0x55)));
+        assert!(style.sub_modifier.contains(Modifier::BOLD));
+
+        assert_eq!(Config::default().selection.indicator(), ">");
+        assert_eq!(
+            Config::default().selection.style().unwrap(),
+            theme::SELECTED_STYLE
+        );
+
+        let config: Config = toml::from_str("[selection]\nfg = \"not a color\"\n").unwrap();
+        assert!(config.selection.style().is_err());
+    }
+
+    #[test]
+    fn test_save_wrap_keeps_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        Config::save_wrap(&path, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "wrap = false\n");
+
+        fs::write(
+            &path,
+            "# mine\nsort_by_time = true\nwrap = false\n\n[preview]\nwrap = 1\n",
+        )
+        .unwrap();
+        Config::save_wrap(&path, true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# mine\nsort_by_time = true\nwrap = true\n\n[preview]\nwrap = 1\n"
+        );
+        let config: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.wrap, Some(true));
+    }
+
+    #[test]
+    fn test_tag_levels() {
+        let config: Config =
+            toml::from_str("[tag_levels]\nCrash = \"error\"\nanr = \"WARN\"\n").unwrap();
+        let tag_levels = config.tag_levels().unwrap();
+        assert_eq!(tag_levels.get("crash").map(String::as_str), Some("ERROR"));
+        assert_eq!(tag_levels.get("anr").map(String::as_str), Some("WARN"));
+
+        let config: Config = toml::from_str("[tag_levels]\ncrash = \"fatal\"\n").unwrap();
+        assert!(config.tag_levels().is_err());
+    }
+
+    #[test]
+    fn test_border_config() {
+        let config: Config =
+            toml::from_str("[borders]\nsides = \"all\"\ncorners = \"square\"\n").unwrap();
+        assert_eq!(config.borders.borders(), Borders::ALL);
+        assert_eq!(config.borders.border_type(), BorderType::Plain);
+
+        let config = Config::default();
+        assert_eq!(config.borders.borders(), Borders::TOP | Borders::LEFT);
+        assert_eq!(config.borders.border_type(), BorderType::Rounded);
+    }
+}
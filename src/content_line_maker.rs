@@ -1,38 +1,234 @@
-use ratatui::text::Line;
+use ratatui::{
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
+use std::ops::Range;
+
+/// Most lines content is wrapped into for one item. The rest are only counted, so a
+/// megabyte on a single line can't freeze the details panel building lines nobody scrolls to.
+pub const MAX_WRAPPED_LINES: usize = 10_000;
 
 pub fn wrap_content_to_lines(content: &str, width: u16) -> Vec<Line<'_>> {
+    let (ranges, hidden) = wrap_ranges(content, width);
+    let mut lines: Vec<Line> = ranges
+        .into_iter()
+        .map(|range| Line::from(&content[range]))
+        .collect();
+    if hidden > 0 {
+        lines.push(truncated_line(hidden));
+    }
+    lines
+}
+
+/// Byte ranges of the lines `content` wraps into at `width`, without building them, so a
+/// caller can count every line but only turn the visible ones into `Line`s. Also returns how
+/// many lines past `MAX_WRAPPED_LINES` were left out.
+pub fn wrap_ranges(content: &str, width: u16) -> (Vec<Range<usize>>, usize) {
     if width == 0 {
-        return vec![];
+        return (vec![], 0);
     }
 
     let width = width as usize;
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
+    let mut ranges = Vec::new();
+    let mut hidden = 0;
+    let mut emit = |range: Range<usize>| {
+        if ranges.len() < MAX_WRAPPED_LINES {
+            ranges.push(range);
+        } else {
+            hidden += 1;
+        }
+    };
 
-    for ch in content.chars() {
+    let mut start = 0;
+    for (i, ch) in content.char_indices() {
         if ch == '\n' {
-            lines.push(Line::from(current_line.clone()));
-            current_line.clear();
-        } else {
-            current_line.push(ch);
-            if current_line.len() == width {
-                lines.push(Line::from(current_line.clone()));
-                current_line.clear();
+            emit(start..i);
+            start = i + 1;
+        } else if i + ch.len_utf8() - start == width {
+            emit(start..i + ch.len_utf8());
+            start = i + ch.len_utf8();
+        }
+    }
+
+    if start < content.len() {
+        emit(start..content.len());
+    }
+
+    (ranges, hidden)
+}
+
+/// `line`, the text starting at byte `line_start` of some content, with the parts inside
+/// `matches` (byte ranges of that same content) patched with `style`. A match crossing a
+/// wrap boundary is highlighted on both lines it was cut into.
+pub fn highlight_matches<'a>(
+    line: Line<'a>,
+    line_start: usize,
+    matches: &[Range<usize>],
+    style: Style,
+) -> Line<'a> {
+    let line_end = line_start
+        + line
+            .spans
+            .iter()
+            .map(|span| span.content.len())
+            .sum::<usize>();
+    if !matches
+        .iter()
+        .any(|m| m.start < line_end && m.end > line_start)
+    {
+        return line;
+    }
+
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    let mut span_start = line_start;
+    for span in line.spans {
+        let span_end = span_start + span.content.len();
+        // Cut points inside this span, relative to it
+        let mut cuts = vec![0, span.content.len()];
+        for m in matches {
+            for point in [m.start, m.end] {
+                if span_start < point && point < span_end {
+                    cuts.push(point - span_start);
+                }
             }
         }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let at = span_start + piece[0];
+            let is_match = matches.iter().any(|m| m.start <= at && at < m.end);
+            let text = span.content[piece[0]..piece[1]].to_string();
+            spans.push(if is_match {
+                Span::styled(text, span.style.patch(style))
+            } else {
+                Span::styled(text, span.style)
+            });
+        }
+        span_start = span_end;
     }
+    Line::from(spans).style(line.style)
+}
+
+/// Stands in for the lines `wrap_ranges` left out
+pub fn truncated_line(hidden: usize) -> Line<'static> {
+    Line::from(format!("… content truncated — {} lines hidden", hidden)).italic()
+}
+
+/// Classic `offset | hex bytes | ascii` dump, 16 bytes per line. Bytes outside printable
+/// ASCII show as `.` in the ascii column.
+pub fn hex_dump_lines(bytes: &[u8]) -> Vec<Line<'static>> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut hex = String::with_capacity(49);
+            for (i, byte) in chunk.iter().enumerate() {
+                if i == 8 {
+                    hex.push(' ');
+                }
+                hex.push_str(&format!("{:02x} ", byte));
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            Line::from(format!("{:08x}  {:<49} |{}|", row * 16, hex, ascii))
+        })
+        .collect()
+}
 
-    if !current_line.is_empty() {
-        lines.push(Line::from(current_line));
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffOp {
+    Same,
+    Added,
+    Removed,
+}
+
+/// Line diff of `old` → `new` via longest common subsequence. Above `MAX_LINES` lines on
+/// either side the table gets too big to build every frame, so the whole text counts as
+/// replaced.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<(DiffOp, &'a str)> {
+    const MAX_LINES: usize = 1000;
+    let old: Vec<&str> = old.split('\n').collect();
+    let new: Vec<&str> = new.split('\n').collect();
+
+    if old.len() > MAX_LINES || new.len() > MAX_LINES {
+        return old
+            .into_iter()
+            .map(|line| (DiffOp::Removed, line))
+            .chain(new.into_iter().map(|line| (DiffOp::Added, line)))
+            .collect();
     }
 
-    lines
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((DiffOp::Same, new[j]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // Removals first on ties, like `diff`
+            ops.push((DiffOp::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffOp::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_highlight_across_wrap() {
+        let content = "connection refused";
+        let (ranges, _) = wrap_ranges(content, 13);
+        let matches = [Range { start: 11, end: 18 }]; // "refused", cut after "re"
+        let lines: Vec<Line> = ranges
+            .iter()
+            .map(|range| {
+                highlight_matches(
+                    Line::from(&content[range.clone()]),
+                    range.start,
+                    &matches,
+                    Style::new().bold(),
+                )
+            })
+            .collect();
+        let highlighted = |line: &Line| -> Vec<String> {
+            line.spans
+                .iter()
+                .filter(|span| span.style == Style::new().bold())
+                .map(|span| span.content.to_string())
+                .collect()
+        };
+        assert_eq!(lines[0].to_string(), "connection re");
+        assert_eq!(highlighted(&lines[0]), ["re"]);
+        assert_eq!(highlighted(&lines[1]), ["fused"]);
+    }
+
     #[test]
     fn test_empty_content() {
         let result = wrap_content_to_lines("", 10);
@@ -95,4 +291,50 @@ mod tests {
         assert_eq!(result[3].to_string(), "needs to b");
         assert_eq!(result[4].to_string(), "e wrapped");
     }
+
+    #[test]
+    fn test_huge_single_line_is_capped() {
+        let content = "x".repeat(10 * (MAX_WRAPPED_LINES + 5) + 3);
+        let result = wrap_content_to_lines(&content, 10);
+        assert_eq!(result.len(), MAX_WRAPPED_LINES + 1);
+        assert_eq!(result[MAX_WRAPPED_LINES - 1].to_string(), "x".repeat(10));
+        assert_eq!(
+            result[MAX_WRAPPED_LINES].to_string(),
+            "… content truncated — 6 lines hidden"
+        );
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let result = hex_dump_lines(b"hello\r\n\tworld, this is long");
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].to_string(),
+            "00000000  68 65 6c 6c 6f 0d 0a 09  77 6f 72 6c 64 2c 20 74  |hello...world, t|"
+        );
+        assert_eq!(
+            result[1].to_string(),
+            "00000010  68 69 73 20 69 73 20 6c  6f 6e 67                 |his is long|"
+        );
+        assert!(hex_dump_lines(b"").is_empty());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let ops = diff_lines(
+            "request\nstatus 200\ndone",
+            "request\nstatus 500\nretry\ndone",
+        );
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Same, "request"),
+                (DiffOp::Removed, "status 200"),
+                (DiffOp::Added, "status 500"),
+                (DiffOp::Added, "retry"),
+                (DiffOp::Same, "done"),
+            ]
+        );
+        assert_eq!(diff_lines("same", "same"), vec![(DiffOp::Same, "same")]);
+    }
 }
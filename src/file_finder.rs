@@ -1,13 +1,23 @@
+use regex::Regex;
 use std::{
     fs,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-pub fn find_latest_live_log(log_dir: &Path) -> Result<PathBuf, String> {
+/// Returns the most recently modified live (non-rotated) log file in `log_dir`.
+/// Files with the same modification time are ordered by name.
+///
+/// `live_pattern` is matched against file names to pick live logs; without one, any `.log`
+/// file that isn't numbered like `file.1.log` counts as live.
+pub fn find_latest_live_log(
+    log_dir: &Path,
+    live_pattern: Option<&Regex>,
+) -> Result<PathBuf, String> {
     let entries = fs::read_dir(log_dir)
         .map_err(|e| format!("Failed to read directory '{}': {}", log_dir.display(), e))?;
 
-    let mut live_log_files: Vec<PathBuf> = entries
+    let mut live_log_files: Vec<(Option<SystemTime>, PathBuf)> = entries
         .filter_map(|entry_result| {
             entry_result.ok().and_then(|entry| {
                 let path = entry.path();
@@ -16,18 +26,20 @@ pub fn find_latest_live_log(log_dir: &Path) -> Result<PathBuf, String> {
                 }
 
                 let file_name = path.file_name()?.to_str()?;
-                if !file_name.ends_with(".log") {
+                // Compressed rotations can never be tailed
+                if file_name.ends_with(".gz") {
                     return None;
                 }
 
-                let base_name = file_name.strip_suffix(".log").unwrap();
-                if let Some(last_dot_pos) = base_name.rfind('.') {
-                    let suffix = &base_name[last_dot_pos + 1..];
-                    if suffix.parse::<u32>().is_ok() {
-                        return None; // Exclude rotated logs like `file.1.log`
-                    }
+                let is_live = match live_pattern {
+                    Some(pattern) => pattern.is_match(file_name),
+                    None => is_default_live_name(file_name),
+                };
+                if !is_live {
+                    return None;
                 }
-                Some(path)
+                let modified = entry.metadata().and_then(|m| m.modified()).ok();
+                Some((modified, path))
             })
         })
         .collect();
@@ -36,6 +48,139 @@ pub fn find_latest_live_log(log_dir: &Path) -> Result<PathBuf, String> {
         return Err("No live log files found in the directory.".to_string());
     }
 
+    // Newest last; the name is a deterministic tiebreak for equal mtimes
     live_log_files.sort();
-    Ok(live_log_files.pop().unwrap())
+    Ok(live_log_files.pop().unwrap().1)
+}
+
+/// The file in `log_dir` with inode `ino`, which is where a live log ends up after being
+/// rotated to a new name
+pub fn find_by_inode(log_dir: &Path, ino: u64) -> Option<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::read_dir(log_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry
+                .metadata()
+                .is_ok_and(|meta| meta.is_file() && meta.ino() == ino)
+        })
+        .map(|entry| entry.path())
+}
+
+fn is_default_live_name(file_name: &str) -> bool {
+    let Some(base_name) = file_name.strip_suffix(".log") else {
+        return false;
+    };
+
+    if let Some(last_dot_pos) = base_name.rfind('.') {
+        let suffix = &base_name[last_dot_pos + 1..];
+        if suffix.parse::<u32>().is_ok() {
+            return false; // Exclude rotated logs like `file.1.log`
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs::File, time::Duration};
+
+    fn touch(dir: &Path, name: &str) {
+        fs::write(dir.join(name), "").unwrap();
+    }
+
+    fn touch_at(dir: &Path, name: &str, secs_after_epoch: u64) {
+        touch(dir, name);
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(secs_after_epoch);
+        File::options()
+            .write(true)
+            .open(dir.join(name))
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_excludes_rotated_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_at(dir.path(), "app.log", 100);
+        touch_at(dir.path(), "other.log", 200);
+        // Rotated logs are ignored even when they are the newest
+        touch_at(dir.path(), "app.1.log", 300);
+        touch_at(dir.path(), "app.2.log", 400);
+
+        let latest = find_latest_live_log(dir.path(), None).unwrap();
+        assert_eq!(latest, dir.path().join("other.log"));
+    }
+
+    #[test]
+    fn test_picks_newest_mtime_over_name() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_at(dir.path(), "preview-9.log", 100);
+        touch_at(dir.path(), "preview-10.log", 200);
+
+        let latest = find_latest_live_log(dir.path(), None).unwrap();
+        assert_eq!(latest, dir.path().join("preview-10.log"));
+    }
+
+    #[test]
+    fn test_equal_mtimes_fall_back_to_name() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_at(dir.path(), "b.log", 100);
+        touch_at(dir.path(), "a.log", 100);
+
+        let latest = find_latest_live_log(dir.path(), None).unwrap();
+        assert_eq!(latest, dir.path().join("b.log"));
+    }
+
+    #[test]
+    fn test_live_pattern_overrides_default() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_at(dir.path(), "app.log", 100);
+        touch_at(dir.path(), "app.log.1", 200);
+        touch_at(dir.path(), "app-2024-01-01.log", 300);
+        touch_at(dir.path(), "app.log.2.gz", 400);
+
+        let pattern = Regex::new(r"^app\.log$").unwrap();
+        let latest = find_latest_live_log(dir.path(), Some(&pattern)).unwrap();
+        assert_eq!(latest, dir.path().join("app.log"));
+    }
+
+    #[test]
+    fn test_gz_never_live() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_at(dir.path(), "app.log", 100);
+        touch_at(dir.path(), "app.log.gz", 200);
+
+        let pattern = Regex::new(r"^app\.log").unwrap();
+        let latest = find_latest_live_log(dir.path(), Some(&pattern)).unwrap();
+        assert_eq!(latest, dir.path().join("app.log"));
+    }
+
+    #[test]
+    fn test_find_rotated_file_by_inode() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        touch(dir.path(), "app.log");
+        let ino = fs::metadata(dir.path().join("app.log")).unwrap().ino();
+        fs::rename(dir.path().join("app.log"), dir.path().join("app.1.log")).unwrap();
+        touch(dir.path(), "app.log");
+
+        let rotated = find_by_inode(dir.path(), ino);
+        assert_eq!(rotated, Some(dir.path().join("app.1.log")));
+    }
+
+    #[test]
+    fn test_no_log_files() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(dir.path(), "notes.txt");
+
+        let err = find_latest_live_log(dir.path(), None).unwrap_err();
+        assert_eq!(err, "No live log files found in the directory.");
+    }
 }
@@ -0,0 +1,139 @@
+//! Searching the log file's raw text, including whatever the parser dropped, for tracking
+//! down logs that seem to vanish between the file and the LOGS list
+
+use crate::log_parser::LogItem;
+
+/// Most matches kept, so a one-letter pattern on a huge file stays quick to list
+pub const MAX_MATCHES: usize = 1000;
+
+/// Lines walked back from a match in search of one that belongs to a parsed log
+const NEAREST_ITEM_LOOKBACK: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    pub line_number: usize, // 1-based
+    pub offset: usize,      // Byte offset of the start of the line
+    pub line: String,
+}
+
+/// The lines of `bytes` containing `pattern`, ignoring case, up to `MAX_MATCHES`
+pub fn grep(bytes: &[u8], pattern: &str) -> Vec<GrepMatch> {
+    let pattern = pattern.to_lowercase();
+    let mut offset = 0;
+    let mut matches = Vec::new();
+    for (i, raw_line) in bytes.split(|&b| b == b'\n').enumerate() {
+        let line = String::from_utf8_lossy(raw_line);
+        if line.to_lowercase().contains(&pattern) {
+            matches.push(GrepMatch {
+                line_number: i + 1,
+                offset,
+                line: line.trim_end_matches('\r').to_string(),
+            });
+            if matches.len() == MAX_MATCHES {
+                break;
+            }
+        }
+        offset += raw_line.len() + 1;
+    }
+    matches
+}
+
+/// Line `line_number` of `bytes` with up to `context` lines on either side, numbered
+pub fn surrounding_lines(bytes: &[u8], line_number: usize, context: usize) -> Vec<(usize, String)> {
+    let first = line_number.saturating_sub(context).max(1);
+    bytes
+        .split(|&b| b == b'\n')
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .skip(first - 1)
+        .take(line_number + context + 1 - first)
+        .map(|(n, line)| {
+            let line = String::from_utf8_lossy(line);
+            (n, line.trim_end_matches('\r').to_string())
+        })
+        .collect()
+}
+
+/// Position in `items` of the parsed log that line `line_number` of `bytes` belongs to, or
+/// of the closest one before it when the line was dropped by the parser.
+///
+/// Items don't remember where in the file they came from, so this goes by text: a line
+/// belongs to an item when one of the item's lines ends it (the header before the content
+/// is cut off when parsing). The n-th line that could be any of several items maps to the
+/// n-th of them.
+pub fn nearest_item(bytes: &[u8], line_number: usize, items: &[LogItem]) -> Option<usize> {
+    let lines: Vec<String> = bytes
+        .split(|&b| b == b'\n')
+        .take(line_number)
+        .map(|line| String::from_utf8_lossy(line).trim().to_string())
+        .collect();
+    let belongs = |line: &str, item: &LogItem| {
+        item.raw_content.lines().any(|item_line| {
+            let item_line = item_line.trim();
+            !item_line.is_empty() && line.ends_with(item_line)
+        })
+    };
+
+    let first = lines.len().saturating_sub(NEAREST_ITEM_LOOKBACK);
+    (first..lines.len()).rev().find_map(|i| {
+        let line = &lines[i];
+        if line.is_empty() {
+            return None;
+        }
+        let owners: Vec<usize> = (0..items.len())
+            .filter(|&j| belongs(line, &items[j]))
+            .collect();
+        let occurrence = lines[..i]
+            .iter()
+            .filter(|l| owners.iter().any(|&j| belongs(l, &items[j])))
+            .count();
+        owners.get(occurrence).or(owners.last()).copied()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_parser::{LogFormat, LogParser};
+
+    const TEXT: &str = "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+                        stray line without a header\n\
+                        ## 2024-01-01 10:00:01 [main] INFO ## [net] connected\n\
+                        ## 2024-01-01 10:00:02 [main] WARN ## [ui] Retrying\n\
+                        \tsecond line\n";
+
+    #[test]
+    fn test_grep() {
+        let matches = grep(TEXT.as_bytes(), "RETRY");
+        assert_eq!(
+            matches,
+            vec![GrepMatch {
+                line_number: 4,
+                offset: 136,
+                line: "## 2024-01-01 10:00:02 [main] WARN ## [ui] Retrying".to_string(),
+            }]
+        );
+        assert_eq!(&TEXT[136..138], "##");
+        assert_eq!(grep(TEXT.as_bytes(), "connected").len(), 2);
+        assert!(grep(TEXT.as_bytes(), "missing").is_empty());
+
+        let around = surrounding_lines(TEXT.as_bytes(), 1, 1);
+        assert_eq!(around.len(), 2);
+        assert_eq!(around[1], (2, "stray line without a header".to_string()));
+    }
+
+    #[test]
+    fn test_nearest_item() {
+        let items = LogParser::new(LogFormat::Douyin).process_delta(TEXT);
+        assert_eq!(items.len(), 3);
+        let bytes = TEXT.as_bytes();
+
+        assert_eq!(nearest_item(bytes, 1, &items), Some(0));
+        // A line without its own header maps to the log above it
+        assert_eq!(nearest_item(bytes, 2, &items), Some(0));
+        // The second "connected" is the second item, not the first
+        assert_eq!(nearest_item(bytes, 3, &items), Some(1));
+        // A continuation line
+        assert_eq!(nearest_item(bytes, 5, &items), Some(2));
+    }
+}
@@ -0,0 +1,356 @@
+use crate::log_parser::LogItem;
+use std::collections::HashMap;
+
+/// A `LogItem` field a filter can target instead of the whole raw line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Level,
+    Origin,
+    Tag,
+    Content,
+}
+
+impl Field {
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Level => "level",
+            Field::Origin => "origin",
+            Field::Tag => "tag",
+            Field::Content => "content",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Field::Level, Field::Origin, Field::Tag, Field::Content]
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn value(self, item: &LogItem) -> &str {
+        match self {
+            Field::Level => &item.level,
+            Field::Origin => &item.origin,
+            Field::Tag => &item.tag,
+            Field::Content => &item.content,
+        }
+    }
+}
+
+/// Exact match on one field, as chosen from the picker
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub field: Field,
+    pub value: String,
+}
+
+impl FieldFilter {
+    pub fn matches(&self, item: &LogItem) -> bool {
+        self.field.value(item) == self.value
+    }
+}
+
+/// A parsed filter box query.
+///
+/// - `field:value` matches that field case-insensitively; other words match anywhere in the
+///   raw line
+/// - `AND` (or just a space), `OR` and `NOT` combine terms, `( )` groups them; the operators
+///   are only recognized in upper case
+/// - a query without any field or operator is one phrase, exactly as typed
+#[derive(Debug, PartialEq, Eq)]
+pub struct Query {
+    expr: Expr,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Expr {
+    Field(Field, String),
+    Text(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn matches(&self, item: &LogItem, raw: &str) -> bool {
+        match self {
+            Expr::Field(field, value) => field.value(item).to_lowercase().contains(value),
+            Expr::Text(text) => raw.contains(text),
+            Expr::Not(expr) => !expr.matches(item, raw),
+            Expr::And(lhs, rhs) => lhs.matches(item, raw) && rhs.matches(item, raw),
+            Expr::Or(lhs, rhs) => lhs.matches(item, raw) || rhs.matches(item, raw),
+        }
+    }
+
+    fn collect_terms<'a>(&'a self, terms: &mut Vec<&'a str>) {
+        match self {
+            Expr::Field(Field::Content, value) | Expr::Text(value) => terms.push(value),
+            Expr::Field(..) | Expr::Not(_) => {}
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.collect_terms(terms);
+                rhs.collect_terms(terms);
+            }
+        }
+    }
+}
+
+impl Query {
+    /// Parses the filter box. Errors describe what is wrong for the footer; callers can fall
+    /// back to `Query::phrase` meanwhile.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input);
+        let is_structured = tokens.iter().any(|token| {
+            matches!(*token, "AND" | "OR" | "NOT" | "(" | ")") || field_term(token).is_some()
+        });
+        if !is_structured {
+            // Unknown `name:` prefixes land here too and are searched as plain text
+            return Ok(Self::phrase(input));
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(Self { expr }),
+            Some(")") => Err("Unmatched ')'".to_string()),
+            Some(token) => Err(format!("Unexpected '{}'", token)),
+        }
+    }
+
+    /// The whole input as a single substring match
+    pub fn phrase(input: &str) -> Self {
+        Self {
+            expr: Expr::Text(input.to_lowercase()),
+        }
+    }
+
+    /// Whether the query is a single whole-line phrase, so typing more can only narrow it
+    pub fn is_plain(&self) -> bool {
+        matches!(self.expr, Expr::Text(_))
+    }
+
+    pub fn matches(&self, item: &LogItem) -> bool {
+        self.expr.matches(item, &item.raw_content.to_lowercase())
+    }
+
+    /// The lower-cased text the query looks for in content, for highlighting matches.
+    /// Negated terms and other fields' values are left out.
+    pub fn content_terms(&self) -> Vec<&str> {
+        let mut terms = Vec::new();
+        self.expr.collect_terms(&mut terms);
+        terms.retain(|term| !term.is_empty());
+        terms
+    }
+}
+
+/// Splits on whitespace, with parentheses as tokens of their own
+fn tokenize(input: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    for word in input.split_whitespace() {
+        let mut rest = word;
+        while !rest.is_empty() {
+            let split = match rest.find(['(', ')']) {
+                Some(0) => 1,
+                Some(i) => i,
+                None => rest.len(),
+            };
+            tokens.push(&rest[..split]);
+            rest = &rest[split..];
+        }
+    }
+    tokens
+}
+
+fn field_term(token: &str) -> Option<Expr> {
+    let (name, value) = token.split_once(':')?;
+    Field::from_name(name).map(|field| Expr::Field(field, value.to_lowercase()))
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some("OR") {
+            self.next();
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    /// `AND` is optional: adjacent terms are ANDed together
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some("AND") => {
+                    self.next();
+                }
+                Some("OR") | Some(")") | None => return Ok(expr),
+                Some(_) => {}
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some("NOT") => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Some("(") => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(")") => Ok(expr),
+                    _ => Err("Missing ')'".to_string()),
+                }
+            }
+            Some(op @ ("AND" | "OR" | ")")) => Err(format!("Expected a term before '{}'", op)),
+            Some(token) => {
+                Ok(field_term(token).unwrap_or_else(|| Expr::Text(token.to_lowercase())))
+            }
+            None => Err("Expected a term at the end".to_string()),
+        }
+    }
+}
+
+/// Every distinct non-empty value of `field` with how many items carry it, most common
+/// first (ties alphabetical)
+pub fn distinct_values(items: &[LogItem], field: Field) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        let value = field.value(item);
+        if !value.is_empty() {
+            *counts.entry(value).or_default() += 1;
+        }
+    }
+
+    let mut values: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_parser::LogParser;
+
+    #[test]
+    fn test_distinct_values_by_count() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [render] INFO ## [ui] drawn\n\
+             ## 2024-01-01 10:00:02 [main] WARN ## [net] retrying\n",
+        );
+
+        assert_eq!(
+            distinct_values(&items, Field::Origin),
+            vec![("main".to_string(), 2), ("render".to_string(), 1)]
+        );
+        assert_eq!(
+            distinct_values(&items, Field::Tag),
+            vec![("net".to_string(), 2), ("ui".to_string(), 1)]
+        );
+    }
+
+    fn parse_items() -> Vec<LogItem> {
+        LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] ERROR ## [network] request failed\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [network] error page cached\n\
+             ## 2024-01-01 10:00:02 [render] ERROR ## [ui] frame dropped\n",
+        )
+    }
+
+    fn matching(query: &str) -> Vec<usize> {
+        let query = Query::parse(query).unwrap();
+        parse_items()
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| query.matches(item))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn test_bare_query_is_one_phrase() {
+        assert!(Query::parse("request failed").unwrap().is_plain());
+        assert_eq!(matching("request failed"), vec![0]);
+        assert_eq!(matching("failed request"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_field_tokens_are_anded() {
+        assert_eq!(matching("level:error"), vec![0, 2]);
+        assert_eq!(matching("level:ERROR tag:network"), vec![0]);
+        assert_eq!(matching("tag:network cached"), vec![1]);
+        assert_eq!(matching("origin:render content:frame"), vec![2]);
+    }
+
+    #[test]
+    fn test_content_terms() {
+        let terms = |input: &str| {
+            let query = Query::parse(input).unwrap();
+            query
+                .content_terms()
+                .iter()
+                .map(|term| term.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(terms("Request Failed"), ["request failed"]);
+        assert_eq!(
+            terms("level:error (timeout OR content:Refused) NOT cached"),
+            ["timeout", "refused"]
+        );
+        assert!(terms("").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_field_is_plain_text() {
+        assert!(Query::parse("host:main").unwrap().is_plain());
+        assert_eq!(matching("host:main"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        assert_eq!(matching("failed OR dropped"), vec![0, 2]);
+        assert_eq!(matching("error AND cached"), vec![1]);
+        assert_eq!(matching("NOT tag:network"), vec![2]);
+        assert_eq!(
+            matching("level:error AND NOT (dropped OR origin:other)"),
+            vec![0]
+        );
+        // Lower-case operators are ordinary words of the phrase
+        assert_eq!(matching("failed or dropped"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_invalid_expressions_are_errors() {
+        assert!(Query::parse("error AND").is_err());
+        assert!(Query::parse("OR error").is_err());
+        assert!(Query::parse("(error OR warn").is_err());
+        assert!(Query::parse("error)").is_err());
+    }
+
+    #[test]
+    fn test_field_filter_is_exact() {
+        let items = LogParser::default()
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [network] connected\n");
+        let filter = FieldFilter {
+            field: Field::Tag,
+            value: "net".to_string(),
+        };
+        assert!(!filter.matches(&items[0]));
+    }
+}
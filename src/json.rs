@@ -0,0 +1,72 @@
+//! Pretty-printing JSON log contents. Object keys keep the order they were written in and
+//! numbers all of their digits.
+
+/// `text` indented one member per line when it is a JSON object or array, surrounding
+/// whitespace allowed. Anything else, including bare strings and numbers, is `None`, as is
+/// JSON nested too deep to parse safely.
+pub fn pretty_print(text: &str) -> Option<String> {
+    let text = text.trim();
+    if !text.starts_with(['{', '[']) {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/// Where the leading `"key":` of a pretty-printed line ends, for highlighting keys
+pub fn key_end(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    if !rest.starts_with('"') {
+        return None;
+    }
+    let mut escaped = false;
+    let close = rest[1..].find(|c| {
+        let is_close = c == '"' && !escaped;
+        escaped = c == '\\' && !escaped;
+        is_close
+    })?;
+    let end = indent + 1 + close + 1;
+    line[end..].starts_with(':').then_some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_print() {
+        assert_eq!(
+            pretty_print(r#" {"id":7,"tags":["a", "b\"c"],"empty":{}, "ok":true} "#).unwrap(),
+            "{\n  \"id\": 7,\n  \"tags\": [\n    \"a\",\n    \"b\\\"c\"\n  ],\n  \"empty\": {},\n  \"ok\": true\n}"
+        );
+        assert_eq!(
+            pretty_print("[-12345678901234567890.125, null]").unwrap(),
+            "[\n  -12345678901234567890.125,\n  null\n]"
+        );
+
+        for not_json in [
+            "42",
+            "\"text\"",
+            "{\"a\":}",
+            "{\"a\":1",
+            "[1] trailing",
+            "[nan]",
+        ] {
+            assert_eq!(pretty_print(not_json), None, "{}", not_json);
+        }
+    }
+
+    #[test]
+    fn test_deep_nesting_is_not_json() {
+        let deep = "[".repeat(100_000) + &"]".repeat(100_000);
+        assert_eq!(pretty_print(&deep), None);
+    }
+
+    #[test]
+    fn test_key_end() {
+        assert_eq!(key_end("  \"na\\\"me\": 1"), Some(11));
+        assert_eq!(key_end("  \"value\","), None);
+        assert_eq!(key_end("{"), None);
+    }
+}
@@ -1,8 +1,18 @@
+use crate::theme;
+use chrono::{DateTime, Datelike, NaiveDateTime};
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use uuid::Uuid;
 
+/// Highest detail level `format_detail` knows; it shows every field
+pub const MAX_DETAIL_LEVEL: u8 = 4;
+
+/// Previewed in place of content that is empty or only whitespace, so the row isn't blank
+pub const EMPTY_CONTENT_PLACEHOLDER: &str = "<empty>";
+
 lazy_static! {
     // Leading header that can appear right at the beginning of the delta
     static ref LEADING_HEADER_RE: Regex = Regex::new(
@@ -14,14 +24,6 @@ lazy_static! {
         r"\[\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d{3}\] \[\w+\]\s*"
     ).unwrap();
 
-    // Marks the start of a regular log item
-    static ref ITEM_SEP_RE: Regex =
-        Regex::new(r"## \d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}").unwrap();
-
-    // Parses a regular log item into timestamp + body
-    static ref ITEM_PARSE_RE: Regex =
-        Regex::new(r"(?s)^## (\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})\s*(.*)").unwrap();
-
     // Extracts:  [origin] LEVEL ## [TAG] message…
     // IMPORTANT: In (?x) mode, `#` starts a comment. Escape the hashes as \#\#.
     static ref CONTENT_HEADER_RE: Regex = Regex::new(
@@ -32,65 +34,357 @@ lazy_static! {
           \[(?P<tag>[^\]]+)]\s*
           (?P<msg>.*)"
     ).unwrap();
+
+    // Extracts:  [origin] LEVEL message…  (the header above without its `## [TAG]`). Without
+    // the `##` to mark the header, only the known levels count, so `[render] OK done` stays
+    // a message.
+    static ref ORIGIN_LEVEL_RE: Regex = Regex::new(&format!(
+        r"(?s)^\[(?P<origin>[^\]]+)]\s*(?P<level>{})(?:\s+(?P<msg>.*))?$",
+        theme::LEVELS.join("|")
+    )).unwrap();
+
+    // Extracts:  host process[pid]: message…
+    static ref SYSLOG_HEADER_RE: Regex = Regex::new(
+        r"(?s)^(?P<host>\S+)\s+(?P<process>[^\s\[:]+)(?:\[(?P<pid>\d+)\])?:\s*(?P<msg>.*)"
+    ).unwrap();
+
+    // `path/to/file.ext:line`, as in panics, stack traces and compiler output
+    static ref SOURCE_LOCATION_RE: Regex = Regex::new(
+        r"(?P<path>(?:[A-Za-z]:)?[\w.\-/\\]*[\w\-]\.[A-Za-z]\w*):(?P<line>\d+)"
+    ).unwrap();
+
+    // Splits a syslog timestamp into month name, day and time of day
+    static ref SYSLOG_TIME_RE: Regex =
+        Regex::new(r"^([A-Z][a-z]{2}) +(\d{1,2}) (\d{2}:\d{2}:\d{2})$").unwrap();
+}
+
+/// Timestamp layout that marks the start of each log item
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `## YYYY-MM-DD HH:MM:SS` items written by DouyinAR
+    #[default]
+    Douyin,
+    /// `YYYY-MM-DDTHH:MM:SS[.fff][Z|±HH:MM]` at the start of a line
+    Iso8601,
+    /// `Mmm dd HH:MM:SS host process[pid]: message` lines
+    Syslog,
+}
+
+impl LogFormat {
+    /// (item separator, item parser) patterns. The parser must capture the timestamp as
+    /// group 1 and the rest of the item as group 2.
+    fn patterns(self) -> (&'static str, &'static str) {
+        match self {
+            LogFormat::Douyin => (
+                r"(?m)^## \d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}",
+                r"(?s)^## (\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})\s*(.*)",
+            ),
+            LogFormat::Iso8601 => (
+                r"(?m)^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}",
+                r"(?s)^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\s*(.*)",
+            ),
+            LogFormat::Syslog => (
+                r"(?m)^[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}",
+                r"(?s)^([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2})\s*(.*)",
+            ),
+        }
+    }
+}
+
+/// `[preview]`: how the one-line content preview in the LOGS list is cut down
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PreviewOptions {
+    /// Columns of content kept before cutting it off with `…`; unlimited when unset
+    pub max_length: Option<usize>,
+    /// Skip leading `{`/`[` so JSON payloads preview their first key instead of a brace
+    pub strip_json_braces: bool,
+    /// Non-empty content lines joined into the preview [default: 1]
+    pub lines: Option<usize>,
+}
+
+/// Display widths the level, origin and tag fields are padded to, so messages line up in a
+/// column. Zero widths leave fields unpadded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnWidths {
+    pub level: usize,
+    pub origin: usize,
+    pub tag: usize,
+}
+
+impl ColumnWidths {
+    /// The widest field of each kind among `items`
+    pub fn of<'a>(items: impl IntoIterator<Item = &'a LogItem>) -> Self {
+        items
+            .into_iter()
+            .fold(Self::default(), |widths, item| Self {
+                level: widths.level.max(item.level.width()),
+                origin: widths.origin.max(item.origin.width()),
+                tag: widths.tag.max(item.tag.width()),
+            })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct LogItem {
     pub id: Uuid,
     pub time: String,
+    pub timestamp: Option<NaiveDateTime>, // `time` parsed; offsets are converted to UTC
     pub level: String,
     pub origin: String,
     pub tag: String,
     pub content: String,
     pub raw_content: String,
+    /// The item's text as read from the file, timestamp and removed headers included
+    pub original: String,
     pub folded_count: u32,
+    pub index: usize, // Position in the app's `raw_logs`, assigned when the item is appended
+    /// Order the item was first parsed in this session, assigned by the app. Unlike `index`
+    /// it never restarts, so items sharing a timestamp keep a stable, visible order.
+    pub seq: u64,
 }
 
 impl LogItem {
-    pub fn contains(&self, pattern: &str) -> bool {
-        self.raw_content
-            .to_lowercase()
-            .contains(&pattern.to_lowercase())
+    /// Rough bytes this item holds, its strings' buffers included
+    pub fn heap_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + [
+                &self.time,
+                &self.level,
+                &self.origin,
+                &self.tag,
+                &self.content,
+                &self.raw_content,
+                &self.original,
+            ]
+            .iter()
+            .map(|s| s.capacity())
+            .sum::<usize>()
+    }
+
+    /// Whether the content is empty or only whitespace, as with header-only lines
+    pub fn has_empty_content(&self) -> bool {
+        self.content.trim().is_empty()
+    }
+
+    /// Whether both items have a timestamp and they fall in the same second
+    pub fn same_second(&self, other: &LogItem) -> bool {
+        match (self.timestamp, other.timestamp) {
+            (Some(a), Some(b)) => a.and_utc().timestamp() == b.and_utc().timestamp(),
+            _ => false,
+        }
+    }
+
+    /// Every `path:line` mentioned in the content, in order. Paths aren't checked to exist,
+    /// so `host.name:443` shows up too.
+    pub fn source_locations(&self) -> Vec<(&str, usize)> {
+        SOURCE_LOCATION_RE
+            .captures_iter(&self.content)
+            .filter_map(|caps| Some((caps.name("path")?.as_str(), caps["line"].parse().ok()?)))
+            .collect()
+    }
+
+    /// The stack trace in the content: the longest run of at least two consecutive frame
+    /// lines (`at …`, `Caused by…`, `... N more`, `#N …` or ones naming a `file:line`), with
+    /// the exception line right above it. `None` when there is no such run.
+    pub fn stack_trace(&self) -> Option<&str> {
+        let is_frame = |line: &str| {
+            let line = line.trim_start();
+            line.starts_with("at ")
+                || line.starts_with("Caused by")
+                || (line.starts_with("...") && line.ends_with("more"))
+                || line
+                    .strip_prefix('#')
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+                || SOURCE_LOCATION_RE.is_match(line)
+        };
+
+        // Byte ranges of each line, then the longest run of frames among them
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in self.content.split('\n') {
+            lines.push(start..start + line.len());
+            start += line.len() + 1;
+        }
+        let mut best: Option<Range<usize>> = None;
+        let mut run_start = None;
+        for (i, range) in lines.iter().enumerate() {
+            let frame = is_frame(&self.content[range.clone()]);
+            match (frame, run_start) {
+                (true, None) => run_start = Some(i),
+                (false, Some(first)) => {
+                    if best.as_ref().is_none_or(|b| i - first > b.len()) {
+                        best = Some(first..i);
+                    }
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(first) = run_start
+            && best.as_ref().is_none_or(|b| lines.len() - first > b.len())
+        {
+            best = Some(first..lines.len());
+        }
+
+        let best = best.filter(|run| run.len() >= 2)?;
+        // The exception and its message usually sit on the line above the first frame
+        let first = match best.start.checked_sub(1) {
+            Some(above) if !self.content[lines[above].clone()].trim().is_empty() => above,
+            _ => best.start,
+        };
+        Some(&self.content[lines[first].start..lines[best.end - 1].end])
+    }
+
+    /// The markdown document the yank key copies and the session recording can write
+    pub fn to_markdown(&self) -> String {
+        format!(
+            "# Formatted Log\n\n## Time:\n\n{}\n\n## Level:\n\n{}\n\n## Origin:\n\n{}\n\n## Tag:\n\n{}\n\n## Content:\n\n{}\n\n# Raw Log\n\n{}",
+            self.time, self.level, self.origin, self.tag, self.content, self.raw_content
+        )
+    }
+
+    /// One `time=… level=… origin=… tag=… content="…"` line, logfmt style, for pasting
+    /// into issue trackers. Content is always quoted; the other fields only when needed.
+    pub fn to_key_value(&self) -> String {
+        return format!(
+            "time={} level={} origin={} tag={} content={}",
+            value(&self.time),
+            value(&self.level),
+            value(&self.origin),
+            value(&self.tag),
+            quoted(&self.content)
+        );
+
+        fn value(field: &str) -> String {
+            if field.is_empty()
+                || field.contains(|c: char| c.is_whitespace() || c == '"' || c == '=')
+            {
+                quoted(field)
+            } else {
+                field.to_string()
+            }
+        }
+
+        fn quoted(field: &str) -> String {
+            let escaped = field
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\r', "\\r")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t");
+            format!("\"{}\"", escaped)
+        }
     }
 
-    pub fn get_preview_text(&self, detail_level: u8) -> String {
+    /// The preview with its fields unpadded
+    #[cfg(test)]
+    pub fn get_preview_text(&self, detail_level: u8, options: &PreviewOptions) -> String {
+        self.get_aligned_preview_text(detail_level, options, &ColumnWidths::default())
+    }
+
+    /// The preview with its bracketed fields padded to `widths`
+    pub fn get_aligned_preview_text(
+        &self,
+        detail_level: u8,
+        options: &PreviewOptions,
+        widths: &ColumnWidths,
+    ) -> String {
         let count_prefix = if self.folded_count > 1 {
             format!("x{} ", self.folded_count)
         } else {
             String::new()
         };
 
-        let content = shorten_content(&self.content);
-
-        let base_format = match detail_level {
-            0 => content,
-            1 => format!("[{}] {}", self.time, content),
-            2 => format!("[{}] [{}] {}", self.time, self.level, content),
-            3 => format!(
-                "[{}] [{}] [{}] {}",
-                self.time, self.level, self.origin, content
-            ),
-            4 => format!(
-                "[{}] [{}] [{}] [{}] {}",
-                self.time, self.level, self.origin, self.tag, content
-            ),
-            _ => format!("[{}] {}", self.time, content), // default to level 1
+        let content = if self.has_empty_content() {
+            EMPTY_CONTENT_PLACEHOLDER.to_string()
+        } else {
+            shorten_content(&self.content, options)
         };
 
-        return format!("{}{}", count_prefix, base_format);
+        return format!(
+            "{}{}",
+            count_prefix,
+            self.format_fields(detail_level, &content, widths)
+        );
+
+        /// Split the content by \n, trim each item, and join the first `lines` trimmed items that
+        /// are not empty, then cut the result to `max_length` columns
+        fn shorten_content(content: &str, options: &PreviewOptions) -> String {
+            const LINE_SEPARATOR: &str = " ⏎ ";
 
-        /// Split the content by \n, trim each item, and find the first trimmed item that is not empty
-        fn shorten_content(content: &str) -> String {
             let lines = content
                 .split('\n')
-                .map(|line| line.trim())
+                .map(|line| {
+                    if options.strip_json_braces {
+                        line.trim_start_matches(|c: char| c.is_whitespace() || c == '{' || c == '[')
+                            .trim_end()
+                    } else {
+                        line.trim()
+                    }
+                })
                 .collect::<Vec<&str>>();
-            for line in lines {
-                if !line.is_empty() {
-                    return line.to_string();
-                }
+            let mut preview = lines
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .take(options.lines.unwrap_or(1).max(1))
+                .collect::<Vec<&str>>()
+                .join(LINE_SEPARATOR);
+            if preview.is_empty() {
+                preview = content.to_string();
+            }
+
+            match options.max_length {
+                Some(max_length) => truncate_to_width(&preview, max_length),
+                None => preview,
+            }
+        }
+
+        fn truncate_to_width(line: &str, max_width: usize) -> String {
+            if line.width() <= max_width {
+                return line.to_string();
             }
-            return content.to_string();
+            // Leave one column for the ellipsis
+            let mut width = 0;
+            let mut cut: String = line
+                .chars()
+                .take_while(|c| {
+                    width += c.width().unwrap_or(0);
+                    width < max_width
+                })
+                .collect();
+            cut.push('…');
+            cut
+        }
+    }
+
+    /// The item with the fields shown at `detail_level`, keeping the full content rather
+    /// than the one-line preview
+    pub fn format_detail(&self, detail_level: u8) -> String {
+        self.format_fields(detail_level, self.content.trim(), &ColumnWidths::default())
+    }
+
+    fn format_fields(&self, detail_level: u8, content: &str, widths: &ColumnWidths) -> String {
+        let level = padded(&self.level, widths.level);
+        let origin = padded(&self.origin, widths.origin);
+        let tag = padded(&self.tag, widths.tag);
+        return match detail_level {
+            0 => content.to_string(),
+            1 => format!("[{}] {}", self.time, content),
+            2 => format!("[{}] {} {}", self.time, level, content),
+            3 => format!("[{}] {} {} {}", self.time, level, origin, content),
+            4 => format!("[{}] {} {} {} {}", self.time, level, origin, tag, content),
+            _ => format!("[{}] {}", self.time, content), // default to level 1
+        };
+
+        // Bracketed, with the padding after the closing bracket
+        fn padded(field: &str, width: usize) -> String {
+            format!(
+                "[{}]{}",
+                field,
+                " ".repeat(width.saturating_sub(field.width()))
+            )
         }
     }
 }
@@ -105,6 +399,8 @@ mod special_events {
     }
 
     pub trait EventMatcher: Sync + Send {
+        /// Shown by the parse inspector when this matcher claims a log
+        fn name(&self) -> &'static str;
         fn capture(&self, text: &str) -> Vec<MatchedEvent>;
     }
 
@@ -143,6 +439,10 @@ mod special_events {
     }
 
     impl EventMatcher for PauseMatcher {
+        fn name(&self) -> &'static str {
+            "pause"
+        }
+
         fn capture(&self, text: &str) -> Vec<MatchedEvent> {
             Self::pause_block_ranges(text)
                 .into_iter()
@@ -151,12 +451,16 @@ mod special_events {
                     item: LogItem {
                         id: Uuid::new_v4(),
                         time: String::new(),
+                        timestamp: None,
                         origin: String::new(),
                         level: String::new(),
                         tag: String::new(),
                         content: "DYEH PAUSE".to_string(),
                         raw_content: "DYEH PAUSE".to_string(),
+                        original: String::new(),
                         folded_count: 1,
+                        index: 0,
+                        seq: 0,
                     },
                 })
                 .collect()
@@ -197,6 +501,10 @@ mod special_events {
     }
 
     impl EventMatcher for ResumeMatcher {
+        fn name(&self) -> &'static str {
+            "resume"
+        }
+
         fn capture(&self, text: &str) -> Vec<MatchedEvent> {
             Self::resume_block_ranges(text)
                 .into_iter()
@@ -205,12 +513,16 @@ mod special_events {
                     item: LogItem {
                         id: Uuid::new_v4(),
                         time: String::new(),
+                        timestamp: None,
                         origin: String::new(),
                         level: String::new(),
                         tag: String::new(),
                         content: "DYEH RESUME".to_string(),
                         raw_content: "DYEH RESUME".to_string(),
+                        original: String::new(),
                         folded_count: 1,
+                        index: 0,
+                        seq: 0,
                     },
                 })
                 .collect()
@@ -231,15 +543,65 @@ fn strip_leading_header(s: &str) -> &str {
         .unwrap_or(s)
 }
 
+// Each header becomes a line break rather than nothing: the header may be all that stood
+// between the end of one item and the start of the next, and separators anchored to the
+// start of a line must still find the second item
 fn remove_inline_headers(s: &str) -> String {
-    INLINE_HEADER_RE.replace_all(s, "").into_owned()
+    INLINE_HEADER_RE.replace_all(s, "\n").into_owned()
 }
 
-// Split “[origin] LEVEL ## [TAG] …” → (origin, level, tag, msg)
+/// A header taken out of a delta: where it was in the delta, and where what replaced it
+/// ends in the cleaned text
+struct Removal {
+    delta: Range<usize>,
+    cleaned_end: usize,
+}
+
+// `remove_inline_headers(strip_leading_header(delta))`, along with the headers it took out
+fn clean_delta(delta: &str) -> (String, Vec<Removal>) {
+    let stripped = strip_leading_header(delta);
+    let lead = delta.len() - stripped.len();
+    let mut removals = Vec::new();
+    if lead > 0 {
+        removals.push(Removal {
+            delta: 0..lead,
+            cleaned_end: 0,
+        });
+    }
+    let mut cleaned = String::with_capacity(stripped.len());
+    let mut last = 0;
+    for m in INLINE_HEADER_RE.find_iter(stripped) {
+        cleaned.push_str(&stripped[last..m.start()]);
+        cleaned.push('\n');
+        removals.push(Removal {
+            delta: lead + m.start()..lead + m.end(),
+            cleaned_end: cleaned.len(),
+        });
+        last = m.end();
+    }
+    cleaned.push_str(&stripped[last..]);
+    (cleaned, removals)
+}
+
+// Offset in the delta of `pos` in the cleaned text. A header removed right before `pos` is
+// counted after it, so it goes with the item starting there.
+fn delta_offset(removals: &[Removal], pos: usize) -> usize {
+    match removals.iter().rev().find(|r| r.cleaned_end <= pos) {
+        Some(r) if r.cleaned_end == pos => r.delta.start,
+        Some(r) => r.delta.end + pos - r.cleaned_end,
+        None => pos,
+    }
+}
+
+// Be robust to BOM/control chars that might precede the first “[”.
+fn trim_header_start(line: &str) -> &str {
+    line.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}' || c.is_control())
+}
+
+// Split “[origin] LEVEL ## [TAG] …” → (origin, level, tag, msg); the tag is empty for
+// “[origin] LEVEL …”
 fn split_header(line: &str) -> (String, String, String, String) {
-    // Be robust to BOM/control chars that might precede the first “[”.
-    let line =
-        line.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}' || c.is_control());
+    let line = trim_header_start(line);
 
     if let Some(caps) = CONTENT_HEADER_RE.captures(line) {
         (
@@ -248,6 +610,16 @@ fn split_header(line: &str) -> (String, String, String, String) {
             caps["tag"].trim().to_owned(),
             caps["msg"].trim().to_owned(),
         )
+    } else if let Some(caps) = ORIGIN_LEVEL_RE.captures(line) {
+        (
+            caps["origin"].trim().to_owned(),
+            caps["level"].trim().to_owned(),
+            String::new(),
+            caps.name("msg")
+                .map_or("", |m| m.as_str())
+                .trim()
+                .to_owned(),
+        )
     } else {
         (
             String::new(),
@@ -258,68 +630,956 @@ fn split_header(line: &str) -> (String, String, String, String) {
     }
 }
 
-fn parse_structured(block: &str) -> Option<LogItem> {
-    ITEM_PARSE_RE.captures(block).map(|caps| {
+// Drop carriage returns (CRLF logs) and show other control codes as their Unicode control
+// pictures so they can't move the cursor or recolor the terminal. `\n` and `\t` stay.
+fn normalize_content(content: &str) -> String {
+    content
+        .chars()
+        .filter(|c| *c != '\r')
+        .map(|c| match c {
+            '\n' | '\t' => c,
+            '\u{0}'..='\u{1f}' => char::from_u32(0x2400 + c as u32).unwrap_or('\u{fffd}'),
+            '\u{7f}' => '\u{2421}',
+            '\u{80}'..='\u{9f}' => '\u{fffd}',
+            _ => c,
+        })
+        .collect()
+}
+
+// Split “host process[pid]: …” → (host, process, msg)
+fn split_syslog_header(line: &str) -> (String, String, String) {
+    if let Some(caps) = SYSLOG_HEADER_RE.captures(line) {
+        (
+            caps["host"].to_owned(),
+            caps["process"].to_owned(),
+            caps["msg"].trim().to_owned(),
+        )
+    } else {
+        (String::new(), String::new(), line.trim().to_owned())
+    }
+}
+
+// Rewrite “Mmm dd HH:MM:SS” as “YYYY-MM-DD HH:MM:SS”. Syslog omits the year, so the current
+// one is assumed: entries from December read in January end up dated a year ahead.
+fn infer_syslog_year(time: &str, year: i32) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let Some(caps) = SYSLOG_TIME_RE.captures(time) else {
+        return time.to_owned();
+    };
+    let Some(month) = MONTHS.iter().position(|m| *m == &caps[1]) else {
+        return time.to_owned();
+    };
+    let day: u32 = caps[2].parse().unwrap_or(1);
+    format!("{}-{:02}-{:02} {}", year, month + 1, day, &caps[3])
+}
+
+// Parses the `time` of any supported format. Syslog times must already carry a year.
+fn parse_timestamp(time: &str) -> Option<NaiveDateTime> {
+    let time = time.replace(',', ".");
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&time) {
+        return Some(dt.naive_utc());
+    }
+    if let Ok(dt) = DateTime::parse_from_str(&time, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.naive_utc());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&time, format).ok())
+}
+
+/// Stable sort by timestamp. Items without one (special events) keep their place after the
+/// timestamped item that preceded them.
+/// Ties go by `seq`, the order the items were parsed in.
+pub fn sort_by_time(items: &mut Vec<LogItem>) {
+    let mut last = None;
+    let mut keyed: Vec<(Option<NaiveDateTime>, LogItem)> = std::mem::take(items)
+        .into_iter()
+        .map(|item| {
+            last = item.timestamp.or(last);
+            (last, item)
+        })
+        .collect();
+    keyed.sort_by_key(|(key, item)| (*key, item.seq));
+    *items = keyed.into_iter().map(|(_, item)| item).collect();
+}
+
+/// Folds every run of more than `threshold` consecutive identical items (same level,
+/// origin, tag and content) into one with `folded_count` set to the run length. It keeps the
+/// first item's id, so a selected run stays selected as it grows, and the newest item's
+/// time. Shorter runs are left as they are.
+pub fn fold_repeats(items: Vec<LogItem>, threshold: u32) -> Vec<LogItem> {
+    let same = |a: &LogItem, b: &LogItem| {
+        a.level == b.level && a.origin == b.origin && a.tag == b.tag && a.content == b.content
+    };
+    let mut folded: Vec<LogItem> = Vec::with_capacity(items.len());
+    let mut run_start = 0;
+    for item in items {
+        if folded.last().is_some_and(|last| !same(last, &item)) {
+            fold_run(&mut folded, run_start, threshold);
+            run_start = folded.len();
+        }
+        folded.push(item);
+    }
+    fold_run(&mut folded, run_start, threshold);
+    return folded;
+
+    fn fold_run(items: &mut Vec<LogItem>, start: usize, threshold: u32) {
+        let count = (items.len() - start) as u32;
+        if count <= threshold {
+            return;
+        }
+        let id = items[start].id;
+        let newest = items.pop().expect("run is not empty");
+        items.truncate(start);
+        items.push(LogItem {
+            id,
+            folded_count: count,
+            ..newest
+        });
+    }
+}
+
+fn parse_structured(item_parse_re: &Regex, block: &str) -> Option<LogItem> {
+    item_parse_re.captures(block).map(|caps| {
         let raw_content = caps.get(2).map_or("", |m| m.as_str()).trim().to_string();
         LogItem {
             id: Uuid::new_v4(),
             time: caps.get(1).map_or("", |m| m.as_str()).to_string(),
+            timestamp: None,
             origin: String::new(),
             level: String::new(),
             tag: String::new(),
             content: raw_content.clone(),
             raw_content,
+            original: String::new(),
             folded_count: 1,
+            index: 0,
+            seq: 0,
         }
     })
 }
 
+/// One step of parsing a log and what it did, as listed by the parse inspector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStage {
+    pub name: &'static str,
+    pub output: String,
+}
+
 /* ─────────────────────────────── API ──────────────────────────────────── */
-pub fn process_delta(delta: &str) -> Vec<LogItem> {
-    /* 1 ── initial cleaning --------------------------------------------- */
-    let body = remove_inline_headers(strip_leading_header(delta))
-        .trim()
-        .to_string();
-    if body.is_empty() {
-        return Vec::new();
-    }
-
-    /* 2 ── collect *positioned* special events -------------------------- */
-    let mut positioned: Vec<(usize, LogItem)> = Vec::new();
-    for matcher in MATCHERS.iter() {
-        for MatchedEvent { span, item } in matcher.capture(&body) {
-            positioned.push((span.start, item));
+/// Splits raw log text into items using the regexes of one `LogFormat`, compiled once
+pub struct LogParser {
+    format: LogFormat,
+    // Marks the start of a regular log item
+    item_sep_re: Regex,
+    // Parses a regular log item into timestamp + body
+    item_parse_re: Regex,
+}
+
+impl LogParser {
+    pub fn new(format: LogFormat) -> Self {
+        let (item_sep, item_parse) = format.patterns();
+        Self {
+            format,
+            item_sep_re: Regex::new(item_sep).unwrap(),
+            item_parse_re: Regex::new(item_parse).unwrap(),
         }
     }
 
-    /* 3 ── parse the regular “## …” items ------------------------------- */
-    let mut starts: Vec<usize> = ITEM_SEP_RE.find_iter(&body).map(|m| m.start()).collect();
-
-    if !starts.is_empty() {
-        starts.push(body.len()); // sentinel
-        for win in starts.windows(2) {
-            if let [s, e] = *win {
-                if let Some(mut it) = parse_structured(&body[s..e]) {
-                    let (o, l, t, msg) = split_header(&it.content);
-                    it.origin = o;
-                    it.level = l;
-                    it.tag = t;
-                    it.content = msg;
-                    positioned.push((s, it));
+    /// Byte offset of the last item start in `text` other than one at offset 0, i.e. where
+    /// the text can be cut without splitting an item across two deltas
+    pub fn last_item_start(&self, text: &str) -> Option<usize> {
+        self.item_sep_re
+            .find_iter(text)
+            .map(|m| m.start())
+            .filter(|&start| start > 0)
+            .last()
+    }
+
+    pub fn process_delta(&self, delta: &str) -> Vec<LogItem> {
+        /* 1 ── initial cleaning --------------------------------------------- */
+        let (cleaned, removals) = clean_delta(delta);
+        let lead = cleaned.len() - cleaned.trim_start().len();
+        let body = cleaned.trim().to_string();
+        if body.is_empty() {
+            return Vec::new();
+        }
+        // The text of `span` of the body as it was in the delta
+        let original = |span: Range<usize>| {
+            let start = delta_offset(&removals, span.start + lead);
+            let end = delta_offset(&removals, span.end + lead);
+            delta[start..end.max(start)].trim_end().to_string()
+        };
+
+        /* 2 ── collect *positioned* special events -------------------------- */
+        let mut positioned: Vec<(usize, LogItem)> = Vec::new();
+        for matcher in MATCHERS.iter() {
+            for MatchedEvent { span, mut item } in matcher.capture(&body) {
+                item.original = original(span.clone());
+                positioned.push((span.start, item));
+            }
+        }
+
+        /* 3 ── parse the regular “## …” items ------------------------------- */
+        let mut starts: Vec<usize> = self
+            .item_sep_re
+            .find_iter(&body)
+            .map(|m| m.start())
+            .collect();
+
+        if !starts.is_empty() {
+            starts.push(body.len()); // sentinel
+            for win in starts.windows(2) {
+                if let [s, e] = *win {
+                    if let Some(mut it) = parse_structured(&self.item_parse_re, &body[s..e]) {
+                        if self.format == LogFormat::Syslog {
+                            let (host, process, msg) = split_syslog_header(&it.content);
+                            it.time = infer_syslog_year(&it.time, chrono::Local::now().year());
+                            it.origin = host;
+                            it.tag = process;
+                            it.content = msg;
+                        } else {
+                            let (o, l, t, msg) = split_header(&it.content);
+                            it.origin = o;
+                            it.level = l;
+                            it.tag = t;
+                            it.content = msg;
+                        }
+                        it.timestamp = parse_timestamp(&it.time);
+                        it.original = original(s..e);
+                        positioned.push((s, it));
+                    }
                 }
             }
         }
+
+        /* 4 ── restore the natural order ------------------------------------ */
+        positioned.sort_by_key(|(pos, _)| *pos);
+
+        /* 5 ── just return them – no collapsing ----------------------------- */
+        positioned
+            .into_iter()
+            .map(|(_, mut it)| {
+                it.folded_count = 1; // keep the field but force it to 1
+                it.content = normalize_content(&it.content);
+                it
+            })
+            .collect()
     }
 
-    /* 4 ── restore the natural order ------------------------------------ */
-    positioned.sort_by_key(|(pos, _)| *pos);
+    /// Runs the parse steps again on `item`'s `original` text and describes what each one
+    /// did, for finding out why a log came out the way it did once a format drifts. A header
+    /// the item starts with is reported as a leading one, even when it was further into the
+    /// delta and removed as an inline one.
+    pub fn inspect(&self, item: &LogItem) -> Vec<ParseStage> {
+        let stage = |name, output: String| ParseStage { name, output };
+        let original = item.original.as_str();
+        let mut stages = vec![stage("Original", original.to_string())];
 
-    /* 5 ── just return them – no collapsing ----------------------------- */
-    positioned
-        .into_iter()
-        .map(|(_, mut it)| {
-            it.folded_count = 1; // keep the field but force it to 1
-            it
-        })
-        .collect()
+        let stripped = strip_leading_header(original);
+        stages.push(stage(
+            "strip_leading_header",
+            match original.len() - stripped.len() {
+                0 => "nothing removed".to_string(),
+                n => format!("removed {:?}", &original[..n]),
+            },
+        ));
+        let inline: Vec<&str> = INLINE_HEADER_RE
+            .find_iter(stripped)
+            .map(|m| m.as_str())
+            .collect();
+        stages.push(stage(
+            "remove_inline_headers",
+            if inline.is_empty() {
+                "nothing removed".to_string()
+            } else {
+                format!("replaced with line breaks: {:?}", inline)
+            },
+        ));
+        let cleaned = remove_inline_headers(stripped);
+        let body = cleaned.trim();
+
+        // Special events carry no timestamp; all of their text is content
+        let raw = parse_structured(&self.item_parse_re, body)
+            .filter(|_| !item.time.is_empty())
+            .map_or_else(|| body.to_string(), |parsed| parsed.raw_content);
+        stages.extend([
+            stage(
+                "Timestamp",
+                if item.time.is_empty() {
+                    "none: not split off a timestamped item".to_string()
+                } else {
+                    match item.timestamp {
+                        Some(timestamp) => format!("{} (read as {})", item.time, timestamp),
+                        None => format!("{} (not understood as a time)", item.time),
+                    }
+                },
+            ),
+            stage("Raw content", raw.clone()),
+        ]);
+
+        let claimed: Vec<&str> = MATCHERS
+            .iter()
+            .filter(|matcher| !matcher.capture(body).is_empty())
+            .map(|matcher| matcher.name())
+            .collect();
+        stages.push(stage(
+            "Special events",
+            if claimed.is_empty() {
+                "no matcher claims it".to_string()
+            } else {
+                format!("claimed by {}", claimed.join(", "))
+            },
+        ));
+
+        let msg = if self.format == LogFormat::Syslog {
+            let matched = SYSLOG_HEADER_RE.is_match(&raw);
+            let (host, process, msg) = split_syslog_header(&raw);
+            stages.push(stage(
+                "Header",
+                if matched {
+                    format!("host process[pid]: host={:?} process={:?}", host, process)
+                } else {
+                    "no header matched".to_string()
+                },
+            ));
+            msg
+        } else {
+            let line = trim_header_start(&raw);
+            let rule = if CONTENT_HEADER_RE.is_match(line) {
+                Some("[origin] LEVEL ## [TAG]")
+            } else if ORIGIN_LEVEL_RE.is_match(line) {
+                Some("[origin] LEVEL")
+            } else {
+                None
+            };
+            let (origin, level, tag, msg) = split_header(&raw);
+            stages.push(stage(
+                "Header",
+                match rule {
+                    Some(rule) => format!(
+                        "{}: origin={:?} level={:?} tag={:?}",
+                        rule, origin, level, tag
+                    ),
+                    None => "no header matched".to_string(),
+                },
+            ));
+            msg
+        };
+        stages.push(stage("Content", normalize_content(&msg)));
+        stages
+    }
+}
+
+impl Default for LogParser {
+    fn default() -> Self {
+        Self::new(LogFormat::default())
+    }
+}
+
+/// Longest last item kept for re-parsing; past this it is taken as complete, so a file with
+/// no item separators at all isn't parsed over and over
+const MAX_TAIL_BYTES: usize = 1024 * 1024;
+
+/// Parses a log that arrives in pieces, as a growing file does. The last item may not be
+/// complete yet (more of its lines, or the rest of a line, still to be written), so its text
+/// is kept and parsed again in front of the next piece. Fed piece by piece, the items come
+/// out the same as parsing the whole text at once.
+#[derive(Debug, Default)]
+pub struct TailBuffer {
+    tail: String,
+    partial_char: Vec<u8>, // A UTF-8 sequence cut off at the end of the last piece
+    tail_items: usize,     // Items last returned that were parsed from `tail`
+}
+
+impl TailBuffer {
+    /// Parses `bytes`, the text following everything fed so far. Returns how many of the
+    /// items returned before to drop, as they are parsed again at the start of the new
+    /// ones, and the new items.
+    pub fn feed(&mut self, parser: &LogParser, bytes: &[u8]) -> (usize, Vec<LogItem>) {
+        let mut bytes_left = std::mem::take(&mut self.partial_char);
+        bytes_left.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&bytes_left) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes_left.len(),
+        };
+        self.partial_char = bytes_left.split_off(complete);
+
+        let replaced = self.tail_items;
+        let mut text = std::mem::take(&mut self.tail);
+        text.push_str(&String::from_utf8_lossy(&bytes_left));
+        let items = parser.process_delta(&text);
+
+        let tail_start = parser.last_item_start(&text).unwrap_or(0);
+        if text.len() - tail_start <= MAX_TAIL_BYTES {
+            self.tail = text[tail_start..].to_string();
+            self.tail_items = parser.process_delta(&self.tail).len().min(items.len());
+        } else {
+            self.tail_items = 0;
+        }
+        (replaced, items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_douyin_items() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] WARN ## [ui] slow frame\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].time, "2024-01-01 10:00:00");
+        assert_eq!(items[0].origin, "main");
+        assert_eq!(items[0].level, "INFO");
+        assert_eq!(items[0].tag, "net");
+        assert_eq!(items[0].content, "connected");
+        assert_eq!(items[1].level, "WARN");
+    }
+
+    #[test]
+    fn test_inspect_parse() {
+        let parser = LogParser::default();
+        let items = parser.process_delta(
+            "[2024-01-01 10:00:00.000] [main]\n\
+             ## 2024-01-01 10:00:00 [main] WARN ## [net] retrying\n\
+             [2024-01-01 10:00:00.500] [io] still retrying\n\
+             ## 2024-01-01 10:00:01 [render] INFO frame drawn\n\
+             ## 2024-01-01 10:00:02 [main] INFO ## [fx] bef_effect_onPause_imp(0x1)\n",
+        );
+        let outputs = |item: &LogItem| -> Vec<(&'static str, String)> {
+            parser
+                .inspect(item)
+                .into_iter()
+                .map(|stage| (stage.name, stage.output))
+                .collect()
+        };
+
+        let stages = outputs(&items[0]);
+        assert_eq!(
+            stages.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            [
+                "Original",
+                "strip_leading_header",
+                "remove_inline_headers",
+                "Timestamp",
+                "Raw content",
+                "Special events",
+                "Header",
+                "Content"
+            ]
+        );
+        assert_eq!(
+            stages[1].1,
+            r#"removed "[2024-01-01 10:00:00.000] [main]\n""#
+        );
+        assert_eq!(
+            stages[2].1,
+            r#"replaced with line breaks: ["[2024-01-01 10:00:00.500] [io] "]"#
+        );
+        assert_eq!(
+            stages[3].1,
+            "2024-01-01 10:00:00 (read as 2024-01-01 10:00:00)"
+        );
+        assert_eq!(stages[5].1, "no matcher claims it");
+        assert_eq!(
+            stages[6].1,
+            r#"[origin] LEVEL ## [TAG]: origin="main" level="WARN" tag="net""#
+        );
+        // What the parser made of it
+        assert_eq!(stages[7].1, items[0].content);
+        assert_eq!(stages[7].1, "retrying\n\nstill retrying");
+
+        let stages = outputs(&items[1]);
+        assert_eq!(stages[1].1, "nothing removed");
+        assert_eq!(stages[2].1, "nothing removed");
+        assert_eq!(
+            stages[6].1,
+            r#"[origin] LEVEL: origin="render" level="INFO" tag="""#
+        );
+
+        let pause = items
+            .iter()
+            .find(|item| item.content == "DYEH PAUSE")
+            .unwrap();
+        assert_eq!(outputs(pause)[5].1, "claimed by pause");
+    }
+
+    #[test]
+    fn test_split_header_with_and_without_tag() {
+        assert_eq!(
+            split_header("[main] INFO ## [net] connected\nsecond line"),
+            (
+                "main".to_string(),
+                "INFO".to_string(),
+                "net".to_string(),
+                "connected\nsecond line".to_string()
+            )
+        );
+        assert_eq!(
+            split_header("[render] ERROR frame dropped"),
+            (
+                "render".to_string(),
+                "ERROR".to_string(),
+                String::new(),
+                "frame dropped".to_string()
+            )
+        );
+        assert_eq!(
+            split_header("[render] WARN"),
+            (
+                "render".to_string(),
+                "WARN".to_string(),
+                String::new(),
+                String::new()
+            )
+        );
+        // Neither form: all of it is the message
+        assert_eq!(
+            split_header("[render] lowercase isn't a level"),
+            (
+                String::new(),
+                String::new(),
+                String::new(),
+                "[render] lowercase isn't a level".to_string()
+            )
+        );
+        for not_a_level in [
+            "[render] OK done",
+            "[main] URL https://example.com",
+            "[main] INFOS",
+        ] {
+            assert_eq!(split_header(not_a_level).1, "", "{}", not_a_level);
+        }
+    }
+
+    #[test]
+    fn test_markdown_heading_in_content_stays_in_item() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] release notes\n\
+             ## Fixes\n\
+             - reconnect\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] done\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "release notes\n## Fixes\n- reconnect");
+        assert_eq!(items[1].content, "done");
+    }
+
+    #[test]
+    fn test_mid_line_timestamp_does_not_split_items() {
+        let parser = LogParser::default();
+        let items = parser.process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] replaying ## 2024-01-01 09:59:00 entries\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] done\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "replaying ## 2024-01-01 09:59:00 entries");
+        assert_eq!(items[1].time, "2024-01-01 10:00:01");
+
+        // Cutting a large delta uses the same separator
+        let text = "## 2024-01-01 10:00:00 [main] INFO ## [net] a ## 2024-01-01 09:00:00\n";
+        assert_eq!(parser.last_item_start(text), None);
+    }
+
+    /// The fields of `items` that parsing decides, leaving out the random ids
+    fn parsed_fields(items: &[LogItem]) -> Vec<[String; 6]> {
+        items
+            .iter()
+            .map(|item| {
+                [
+                    item.time.clone(),
+                    item.level.clone(),
+                    item.origin.clone(),
+                    item.tag.clone(),
+                    item.content.clone(),
+                    item.raw_content.clone(),
+                ]
+            })
+            .collect()
+    }
+
+    /// Feeds `text` through a `TailBuffer` cut at `cuts` (byte offsets, ascending) and
+    /// checks the result is what parsing it whole gives
+    fn assert_pieces_parse_like_whole(parser: &LogParser, text: &str, cuts: &[usize]) {
+        let mut buffer = TailBuffer::default();
+        let mut items: Vec<LogItem> = Vec::new();
+        let bounds: Vec<usize> = std::iter::once(0)
+            .chain(cuts.iter().copied())
+            .chain(std::iter::once(text.len()))
+            .collect();
+        for piece in bounds.windows(2) {
+            let (replaced, new_items) = buffer.feed(parser, &text.as_bytes()[piece[0]..piece[1]]);
+            items.truncate(items.len() - replaced);
+            items.extend(new_items);
+        }
+        assert_eq!(
+            parsed_fields(&items),
+            parsed_fields(&parser.process_delta(text)),
+            "cut at {:?}",
+            cuts
+        );
+    }
+
+    #[test]
+    fn test_pieces_parse_like_whole() {
+        let douyin = LogParser::default();
+        let fixtures = [
+            (
+                &douyin,
+                "[2024-01-01 10:00:00.000] [main]\n\
+                 ## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+                 ## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n\
+                 \tat Net.send(Net.java:42)\r\n\
+                 \tat Main.run(Main.java:7)\n\
+                 [2024-01-01 10:00:02.000] [main]\n\
+                 ## 2024-01-01 10:00:02 [render] WARN 帧率下降 ⚠\n",
+            ),
+            (
+                &douyin,
+                "## 2024-01-01 10:00:00 [main] INFO ## [effect] bef_effect_onpause_imp(0x1)\n\
+                 ## 2024-01-01 10:00:01 [main] INFO ## [effect] paused\n\
+                 ## 2024-01-01 10:00:02 [main] INFO ## [effect] bef_effect_onresume_imp(0x1)\n",
+            ),
+        ];
+        let iso = LogParser::new(LogFormat::Iso8601);
+        let iso_text =
+            "2024-01-01T10:00:00Z first\n  more of first\n2024-01-01T10:00:01.5+08:00 next\n";
+
+        assert!(
+            douyin
+                .process_delta(fixtures[1].1)
+                .iter()
+                .any(|item| item.content == "DYEH PAUSE")
+        );
+
+        for (parser, text) in fixtures.into_iter().chain([(&iso, iso_text)]) {
+            // Every single cut, then every piece a few bytes long
+            for cut in 1..text.len() {
+                assert_pieces_parse_like_whole(parser, text, &[cut]);
+            }
+            for step in [1, 3, 7, 16] {
+                let cuts: Vec<usize> = (step..text.len()).step_by(step).collect();
+                assert_pieces_parse_like_whole(parser, text, &cuts);
+            }
+        }
+    }
+
+    #[test]
+    fn test_iso8601_items() {
+        let items = LogParser::new(LogFormat::Iso8601).process_delta(
+            "2024-01-01T10:00:00.123+08:00 first\n\
+             continued\n\
+             2024-01-01T10:00:01Z second\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].time, "2024-01-01T10:00:00.123+08:00");
+        assert_eq!(items[0].content, "first\ncontinued");
+        assert_eq!(items[1].time, "2024-01-01T10:00:01Z");
+        assert_eq!(items[1].content, "second");
+    }
+
+    #[test]
+    fn test_original_keeps_removed_headers() {
+        let text = "[2024-01-01 10:00:00.000] [main]\n\
+                    ## 2024-01-01 10:00:00 [main] INFO ## [net] first\n\
+                    \tmore of first\n\
+                    [2024-01-01 10:00:01.000] [main]\n\
+                    ## 2024-01-01 10:00:01 [main] INFO ## [net] second\n";
+        let items = LogParser::default().process_delta(text);
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[0].original,
+            "[2024-01-01 10:00:00.000] [main]\n\
+             ## 2024-01-01 10:00:00 [main] INFO ## [net] first\n\
+             \tmore of first"
+        );
+        assert_eq!(
+            items[1].original,
+            "[2024-01-01 10:00:01.000] [main]\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] second"
+        );
+    }
+
+    #[test]
+    fn test_inline_header_keeps_items_apart() {
+        let items = LogParser::new(LogFormat::Iso8601).process_delta(
+            "2024-01-01T10:00:00Z first[2024-01-01 10:00:00.000] [main] 2024-01-01T10:00:01Z second\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "first");
+        assert_eq!(items[1].content, "second");
+
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first\n\
+             [2024-01-01 10:00:00.000] [main]\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] second\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "first");
+    }
+
+    #[test]
+    fn test_syslog_items() {
+        let items = LogParser::new(LogFormat::Syslog).process_delta(
+            "Mar  7 09:15:02 mbp kernel[0]: disk0 mounted\n\
+             Mar 17 09:15:03 mbp loginwindow: session started\n",
+        );
+        let year = chrono::Local::now().year();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].time, format!("{}-03-07 09:15:02", year));
+        assert_eq!(items[0].origin, "mbp");
+        assert_eq!(items[0].tag, "kernel");
+        assert_eq!(items[0].content, "disk0 mounted");
+        assert_eq!(items[1].time, format!("{}-03-17 09:15:03", year));
+        assert_eq!(items[1].tag, "loginwindow");
+        assert_eq!(items[1].content, "session started");
+    }
+
+    #[test]
+    fn test_infer_syslog_year() {
+        assert_eq!(
+            infer_syslog_year("Dec 31 23:59:59", 2023),
+            "2023-12-31 23:59:59"
+        );
+        assert_eq!(infer_syslog_year("not a time", 2023), "not a time");
+    }
+
+    #[test]
+    fn test_format_mismatch_yields_no_items() {
+        let items = LogParser::new(LogFormat::Iso8601)
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n");
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn test_timestamps_are_parsed() {
+        let douyin = LogParser::default()
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n");
+        assert_eq!(
+            douyin[0].timestamp.map(|t| t.to_string()),
+            Some("2024-01-01 10:00:00".to_string())
+        );
+
+        let iso = LogParser::new(LogFormat::Iso8601).process_delta(
+            "2024-01-01T10:00:00.123+08:00 first\n2024-01-01T10:00:01Z second\n2024-01-01T10:00:02,5+0100 third\n",
+        );
+        let times: Vec<String> = iso
+            .iter()
+            .map(|item| item.timestamp.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            times,
+            [
+                "2024-01-01 02:00:00.123",
+                "2024-01-01 10:00:01",
+                "2024-01-01 09:00:02.500"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_time_keeps_untimed_items_in_place() {
+        let mut items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:05 [main] INFO ## [net] late\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] early\n\
+             ## 2024-01-01 10:00:03 [main] INFO ## [net] middle\n",
+        );
+        // Stand-in for a special event that follows "early"
+        let mut event = items[1].clone();
+        event.timestamp = None;
+        event.content = "paused".to_string();
+        items.insert(2, event);
+
+        sort_by_time(&mut items);
+        let order: Vec<&str> = items.iter().map(|item| item.content.as_str()).collect();
+        assert_eq!(order, ["early", "paused", "middle", "late"]);
+    }
+
+    #[test]
+    fn test_aligned_preview_columns() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first\n\
+             ## 2024-01-01 10:00:01 [render] ERROR ## [ui] second\n",
+        );
+        let widths = ColumnWidths::of(&items);
+        assert_eq!(
+            widths,
+            ColumnWidths {
+                level: 5,
+                origin: 6,
+                tag: 3
+            }
+        );
+        let options = PreviewOptions::default();
+        let rows: Vec<String> = items
+            .iter()
+            .map(|item| item.get_aligned_preview_text(4, &options, &widths))
+            .collect();
+        assert_eq!(
+            rows[0],
+            "[2024-01-01 10:00:00] [INFO]  [main]   [net] first"
+        );
+        assert_eq!(
+            rows[1],
+            "[2024-01-01 10:00:01] [ERROR] [render] [ui]  second"
+        );
+        assert_eq!(
+            items[0].get_preview_text(4, &options),
+            "[2024-01-01 10:00:00] [INFO] [main] [net] first"
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        let items = LogParser::default()
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [net] said \"hi\"\n\tand left\n");
+        assert_eq!(
+            items[0].to_key_value(),
+            r#"time="2024-01-01 10:00:00" level=INFO origin=main tag=net content="said \"hi\"\n\tand left""#
+        );
+    }
+
+    #[test]
+    fn test_stack_trace() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] ERROR ## [net] request failed, retrying\n\
+             java.lang.IllegalStateException: closed\n\
+             \tat com.example.Net.send(Net.java:42)\n\
+             \tat com.example.Main.run(Main.java:7)\n\
+             Caused by: java.io.IOException: reset\n\
+             \t... 3 more\n\
+             \n\
+             see main.rs:1 for details\n\
+             ## 2024-01-01 10:00:01 [main] ERROR ## [net] refused at main.rs:9\n",
+        );
+        assert_eq!(
+            items[0].stack_trace(),
+            Some(
+                "java.lang.IllegalStateException: closed\n\
+                 \tat com.example.Net.send(Net.java:42)\n\
+                 \tat com.example.Main.run(Main.java:7)\n\
+                 Caused by: java.io.IOException: reset\n\
+                 \t... 3 more"
+            )
+        );
+        // A lone file:line isn't a trace
+        assert_eq!(items[1].stack_trace(), None);
+    }
+
+    #[test]
+    fn test_fold_repeats_above_threshold() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] retry\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] retry\n\
+             ## 2024-01-01 10:00:02 [main] INFO ## [net] retry\n\
+             ## 2024-01-01 10:00:03 [main] INFO ## [net] ok\n\
+             ## 2024-01-01 10:00:04 [main] WARN ## [net] ok\n\
+             ## 2024-01-01 10:00:05 [main] WARN ## [net] ok\n",
+        );
+        let first_retry = items[0].id;
+
+        let folded = fold_repeats(items.clone(), 2);
+        let rows: Vec<(&str, u32)> = folded
+            .iter()
+            .map(|item| (item.content.as_str(), item.folded_count))
+            .collect();
+        assert_eq!(rows, [("retry", 3), ("ok", 1), ("ok", 1), ("ok", 1)]);
+        assert_eq!(folded[0].id, first_retry);
+        assert_eq!(folded[0].time, "2024-01-01 10:00:02");
+
+        assert_eq!(fold_repeats(items, 3).len(), 6);
+    }
+
+    #[test]
+    fn test_crlf_and_control_chars_are_normalized() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] first line\r\nsecond\tline\r\n\
+             ## 2024-01-01 10:00:01 [main] INFO ## [net] bell\u{7} and \u{1b}[31mescape\r\n",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "first line\nsecond\tline");
+        assert_eq!(items[0].level, "INFO");
+        assert!(items[0].raw_content.contains("\r\n"));
+        assert_eq!(items[1].content, "bell\u{2407} and \u{241b}[31mescape");
+    }
+
+    #[test]
+    fn test_preview_options() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] {\n  \"status\": 200, \"body\": \"ok\"\n}\n",
+        );
+        let plain = PreviewOptions::default();
+        assert_eq!(items[0].get_preview_text(0, &plain), "{");
+
+        let stripped = PreviewOptions {
+            strip_json_braces: true,
+            ..plain
+        };
+        assert_eq!(
+            items[0].get_preview_text(0, &stripped),
+            "\"status\": 200, \"body\": \"ok\""
+        );
+
+        let cut = PreviewOptions {
+            max_length: Some(10),
+            strip_json_braces: true,
+            ..plain
+        };
+        assert_eq!(items[0].get_preview_text(0, &cut), "\"status\":…");
+        assert_eq!(
+            items[0].get_preview_text(1, &cut),
+            "[2024-01-01 10:00:00] \"status\":…"
+        );
+
+        // Exactly max_length columns fit without an ellipsis; wide chars count double
+        let cjk = LogParser::default()
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [net] 日本語です\n");
+        let fit = PreviewOptions {
+            max_length: Some(10),
+            ..plain
+        };
+        assert_eq!(cjk[0].get_preview_text(0, &fit), "日本語です");
+        let short = PreviewOptions {
+            max_length: Some(7),
+            ..plain
+        };
+        assert_eq!(cjk[0].get_preview_text(0, &short), "日本語…");
+    }
+
+    #[test]
+    fn test_multiline_preview() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] ERROR ## [net] Request failed\n\n  at connect()\n  at main()\n",
+        );
+        let options = PreviewOptions {
+            lines: Some(2),
+            ..PreviewOptions::default()
+        };
+        assert_eq!(
+            items[0].get_preview_text(0, &options),
+            "Request failed ⏎ at connect()"
+        );
+        assert_eq!(
+            items[0].get_preview_text(0, &PreviewOptions::default()),
+            "Request failed"
+        );
+    }
+
+    #[test]
+    fn test_source_locations() {
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] ERROR ## [core] panicked at src/app.rs:42:9, \
+             called from C:\\work\\render.cpp:7 via 127.0.0.1:8080\n",
+        );
+        assert_eq!(
+            items[0].source_locations(),
+            vec![("src/app.rs", 42), ("C:\\work\\render.cpp", 7)]
+        );
+    }
 }
@@ -1,39 +1,57 @@
+mod action;
 mod app;
 mod app_block;
+mod cli;
+mod config;
 mod content_line_maker;
 mod file_finder;
+mod file_grep;
+mod filter;
+mod json;
 mod log_list;
 mod log_parser;
 mod metadata;
+mod preset;
+mod recorder;
 mod theme;
+mod tui;
 mod ui_logger;
 
-use crossterm::event;
-use ratatui::{
-    Terminal,
-    backend::CrosstermBackend,
-    crossterm::{
-        event::{DisableMouseCapture, EnableMouseCapture},
-        execute,
-        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-    },
-};
 use std::io;
 use std::panic;
-use std::time::Duration;
 
 fn main() -> io::Result<()> {
-    let mut terminal = setup_terminal()?;
+    // Parse arguments before entering the alternate screen so `--help`/`--version`
+    // output lands in the user's shell.
+    let cli = cli::Cli::parse_args();
+    let mut config = match config::Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            std::process::exit(1);
+        }
+    };
+    cli.apply_to(&mut config);
+
+    if cli.dump {
+        if let Err(err) = app::dump(&config, cli.path, cli.lines, &mut io::stdout().lock()) {
+            eprintln!("{:?}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut terminal = tui::setup_terminal()?;
 
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
-        restore_terminal().unwrap();
+        tui::restore_terminal().unwrap();
         original_hook(panic_info);
     }));
 
-    let app_result = app::start(&mut terminal);
+    let app_result = app::start(&mut terminal, config, cli.path);
 
-    restore_terminal()?;
+    tui::restore_terminal()?;
 
     if let Err(err) = app_result {
         println!("Application Error: {:?}", err);
@@ -41,34 +59,3 @@ fn main() -> io::Result<()> {
 
     Ok(())
 }
-
-fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
-    // Enable raw mode to process key events without OS interference.
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    // Enter the alternate screen to not mess with the user's shell history.
-    // Enable mouse capture to receive mouse events.
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    Terminal::new(backend)
-}
-
-fn restore_terminal() -> io::Result<()> {
-    let mut stdout = io::stdout();
-
-    // stop the terminal from sending further mouse packets
-    execute!(stdout, DisableMouseCapture)?;
-
-    // switch back to the normal screen (still in raw mode, no echo)
-    execute!(stdout, LeaveAlternateScreen)?;
-
-    // drain any pending events so nothing is left in the input buffer.
-    while event::poll(Duration::from_millis(0))? {
-        let _ = event::read()?;
-    }
-
-    // finally leave raw mode (echo/canonical back on)
-    disable_raw_mode()?;
-
-    Ok(())
-}
@@ -1,4 +1,10 @@
-use std::{ffi::CString, io, path::Path};
+use std::{ffi::CString, io, path::Path, time::Duration};
+
+/// Wait between checks of the log file while it keeps changing
+pub const FAST_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Unchanged checks in a row before the wait starts growing
+const IDLE_POLLS_BEFORE_BACKOFF: u32 = 10;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TimeSpec {
@@ -9,6 +15,7 @@ pub struct TimeSpec {
 #[derive(Clone, Debug)]
 pub struct MetaSnap {
     pub len: u64,
+    pub ino: u64, // Changes when the path is given a new file, e.g. on rotation
     pub mtime: TimeSpec,
 }
 
@@ -27,6 +34,7 @@ pub fn stat_path(path: &Path) -> io::Result<MetaSnap> {
 
     Ok(MetaSnap {
         len: st.st_size as u64,
+        ino: st.st_ino,
         mtime: TimeSpec {
             sec: st.st_mtime as i64,
             nsec: st.st_mtime_nsec as i64,
@@ -40,3 +48,65 @@ pub fn has_changed(prev: &Option<MetaSnap>, cur: &MetaSnap) -> bool {
         Some(p) => p.len != cur.len || p.mtime != cur.mtime,
     }
 }
+
+/// How long to wait between checks of the log file: `FAST_POLL_INTERVAL` while it changes,
+/// then doubling once it has been idle for a while, up to `max`. A quiet log then costs a
+/// stat every few seconds instead of ten a second.
+#[derive(Clone, Debug)]
+pub struct PollBackoff {
+    max: Duration,
+    idle_polls: u32,
+    interval: Duration,
+}
+
+impl PollBackoff {
+    pub fn new(max: Duration) -> Self {
+        Self {
+            max: max.max(FAST_POLL_INTERVAL),
+            idle_polls: 0,
+            interval: FAST_POLL_INTERVAL,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Counts one check of the file, snapping back to the fast interval when it changed
+    pub fn record(&mut self, changed: bool) {
+        if changed {
+            self.idle_polls = 0;
+            self.interval = FAST_POLL_INTERVAL;
+        } else {
+            self.idle_polls = self.idle_polls.saturating_add(1);
+            if self.idle_polls > IDLE_POLLS_BEFORE_BACKOFF {
+                self.interval = (self.interval * 2).min(self.max);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_backoff() {
+        let mut backoff = PollBackoff::new(Duration::from_millis(500));
+        for _ in 0..IDLE_POLLS_BEFORE_BACKOFF {
+            backoff.record(false);
+        }
+        assert_eq!(backoff.interval(), FAST_POLL_INTERVAL);
+
+        backoff.record(false);
+        assert_eq!(backoff.interval(), Duration::from_millis(200));
+        backoff.record(false);
+        backoff.record(false);
+        assert_eq!(backoff.interval(), Duration::from_millis(500));
+
+        backoff.record(true);
+        assert_eq!(backoff.interval(), FAST_POLL_INTERVAL);
+        backoff.record(false);
+        assert_eq!(backoff.interval(), FAST_POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, path::PathBuf};
+
+/// A saved investigation: the filter box, the levels hidden in the header legend and the
+/// LOGS detail level
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub filter: String,
+    #[serde(default)]
+    pub hidden_levels: Vec<String>,
+    #[serde(default = "default_detail_level")]
+    pub detail_level: u8,
+}
+
+fn default_detail_level() -> u8 {
+    1
+}
+
+/// Presets kept in `<config dir>/termlog/presets.toml`, apart from `config.toml` so saving
+/// one never rewrites the hand-edited config. Their order is that of the 1-9 quick slots.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Presets {
+    #[serde(default, rename = "preset")]
+    pub presets: Vec<Preset>,
+}
+
+impl Presets {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("termlog").join("presets.toml"))
+    }
+
+    /// Reads the presets file, or none when it doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read presets '{}'", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid presets '{}'", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write presets '{}'", path.display()))
+    }
+
+    /// Replaces the preset of the same name in place, or adds it to the end. Returns its
+    /// position.
+    pub fn upsert(&mut self, preset: Preset) -> usize {
+        match self.presets.iter().position(|p| p.name == preset.name) {
+            Some(i) => {
+                self.presets[i] = preset;
+                i
+            }
+            None => {
+                self.presets.push(preset);
+                self.presets.len() - 1
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Preset> {
+        (index < self.presets.len()).then(|| self.presets.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, filter: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            filter: filter.to_string(),
+            hidden_levels: vec!["DEBUG".to_string()],
+            detail_level: 2,
+        }
+    }
+
+    #[test]
+    fn test_presets_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("termlog").join("presets.toml");
+        assert!(Presets::load(&path).unwrap().presets.is_empty());
+
+        let mut presets = Presets::default();
+        assert_eq!(presets.upsert(preset("net", "tag:network")), 0);
+        assert_eq!(presets.upsert(preset("ui", "origin:render")), 1);
+        assert_eq!(presets.upsert(preset("net", "tag:net")), 0);
+        presets.save(&path).unwrap();
+
+        let loaded = Presets::load(&path).unwrap();
+        assert_eq!(loaded.presets, presets.presets);
+        assert_eq!(loaded.presets[0].filter, "tag:net");
+    }
+
+    #[test]
+    fn test_remove_preset() {
+        let mut presets = Presets::default();
+        presets.upsert(preset("net", ""));
+        assert_eq!(presets.remove(3), None);
+        assert_eq!(presets.remove(0).map(|p| p.name), Some("net".to_string()));
+        assert!(presets.presets.is_empty());
+    }
+}
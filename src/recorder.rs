@@ -0,0 +1,188 @@
+use crate::log_parser::LogItem;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// How often buffered records are pushed to disk while logs keep arriving
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordFormat {
+    /// The log text exactly as it was read from the file
+    #[default]
+    Raw,
+    /// The same markdown the yank key copies
+    Markdown,
+}
+
+/// Appends every parsed log item of a session to a file
+pub struct Recorder {
+    writer: BufWriter<File>,
+    format: RecordFormat,
+    last_flush: Instant,
+    len: u64,               // Bytes in the file, those still buffered included
+    last_entries: Vec<u64>, // Where each item of the last `record` call starts
+}
+
+impl Recorder {
+    pub fn open(path: &Path, format: RecordFormat) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open recording '{}'", path.display()))?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            writer: BufWriter::new(file),
+            format,
+            last_flush: Instant::now(),
+            len,
+            last_entries: Vec::new(),
+        })
+    }
+
+    /// Appends `items`, first cutting off the last `replaced` items recorded: the leading
+    /// items are those parsed again, maybe with more lines now
+    pub fn record(&mut self, replaced: usize, items: &[LogItem]) -> io::Result<()> {
+        if items.is_empty() && replaced == 0 {
+            return Ok(());
+        }
+        let replaced = replaced.min(self.last_entries.len());
+        if replaced > 0 {
+            let start = self.last_entries[self.last_entries.len() - replaced];
+            self.writer.flush()?;
+            self.writer.get_ref().set_len(start)?;
+            self.len = start;
+        }
+
+        self.last_entries.clear();
+        for item in items {
+            let entry = match self.format {
+                RecordFormat::Raw => format!("{}\n", item.original),
+                RecordFormat::Markdown => format!("{}\n\n", item.to_markdown()),
+            };
+            self.writer.write_all(entry.as_bytes())?;
+            self.last_entries.push(self.len);
+            self.len += entry.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered records if `FLUSH_INTERVAL` has passed since the last flush. Called
+    /// every tick so a quiet log doesn't leave its tail sitting in the buffer.
+    pub fn flush_if_due(&mut self) -> io::Result<()> {
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_parser::LogParser;
+    use std::fs;
+
+    #[test]
+    fn test_records_raw_items_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        let items = LogParser::default().process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] WARN ## [net] retrying\n",
+        );
+
+        let mut recorder = Recorder::open(&path, RecordFormat::Raw).unwrap();
+        recorder.record(0, &items[..1]).unwrap();
+        recorder.record(0, &items[1..]).unwrap();
+        drop(recorder);
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] WARN ## [net] retrying\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrites_items_parsed_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        fs::write(&path, "from an earlier session\n").unwrap();
+        let parser = LogParser::default();
+        let first = parser.process_delta(
+            "## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n",
+        );
+        // The last item grew a line, and another followed it
+        let again = parser.process_delta(
+            "## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n\
+             \tat Net.send(Net.java:42)\n\
+             ## 2024-01-01 10:00:02 [main] INFO ## [net] retried\n",
+        );
+
+        let mut recorder = Recorder::open(&path, RecordFormat::Raw).unwrap();
+        recorder.record(0, &first).unwrap();
+        recorder.record(1, &again).unwrap();
+        drop(recorder);
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "from an earlier session\n\
+             ## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n\
+             ## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n\
+             \tat Net.send(Net.java:42)\n\
+             ## 2024-01-01 10:00:02 [main] INFO ## [net] retried\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrites_markdown_items_parsed_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.md");
+        let parser = LogParser::default();
+        let first = parser.process_delta("## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n");
+        let again = parser.process_delta(
+            "## 2024-01-01 10:00:01 [main] ERROR ## [net] failed\n\
+             \tat Net.send(Net.java:42)\n",
+        );
+
+        let mut recorder = Recorder::open(&path, RecordFormat::Markdown).unwrap();
+        recorder.record(0, &first).unwrap();
+        recorder.record(1, &again).unwrap();
+        drop(recorder);
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n\n", again[0].to_markdown())
+        );
+    }
+
+    #[test]
+    fn test_only_rewrites_what_it_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        fs::write(&path, "from an earlier session\n").unwrap();
+        let items = LogParser::default()
+            .process_delta("## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n");
+
+        // Asked to cut more than it wrote, it leaves what the file held before alone
+        let mut recorder = Recorder::open(&path, RecordFormat::Raw).unwrap();
+        recorder.record(1, &items).unwrap();
+        drop(recorder);
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "from an earlier session\n\
+             ## 2024-01-01 10:00:00 [main] INFO ## [net] connected\n"
+        );
+    }
+}
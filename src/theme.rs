@@ -8,6 +8,7 @@ use ratatui::{
 
 // colors
 pub const TEXT_FG_COLOR: Color = select_color_with_default_palette(PaletteIdx::C200);
+pub const HIDDEN_LEVEL_FG_COLOR: Color = select_color_with_default_palette(PaletteIdx::C600);
 
 // styles
 #[allow(dead_code)]
@@ -17,6 +18,40 @@ pub const LOG_HEADER_STYLE: Style = Style::new()
 pub const SELECTED_STYLE: Style = Style::new()
     .bg(select_color_with_default_palette(PaletteIdx::C700))
     .add_modifier(Modifier::BOLD);
+pub const RANGE_STYLE: Style = Style::new().bg(select_color_with_default_palette(PaletteIdx::C800));
+pub const DIFF_ADDED_STYLE: Style = Style::new().fg(select_color_from_palette(
+    PaletteIdx::C400,
+    palette::tailwind::GREEN,
+));
+pub const DIFF_REMOVED_STYLE: Style = Style::new().fg(select_color_from_palette(
+    PaletteIdx::C400,
+    palette::tailwind::RED,
+));
+pub const DIFF_CHANGED_STYLE: Style = Style::new()
+    .fg(select_color_from_palette(
+        PaletteIdx::C400,
+        palette::tailwind::YELLOW,
+    ))
+    .add_modifier(Modifier::BOLD);
+pub const MATCH_STYLE: Style = Style::new()
+    .fg(select_color_with_default_palette(PaletteIdx::C900))
+    .bg(select_color_from_palette(
+        PaletteIdx::C300,
+        palette::tailwind::YELLOW,
+    ));
+pub const REPEATED_TIME_STYLE: Style = Style::new().fg(HIDDEN_LEVEL_FG_COLOR);
+pub const MARKER_STYLE: Style = Style::new().fg(HIDDEN_LEVEL_FG_COLOR);
+pub const JSON_KEY_STYLE: Style = Style::new().fg(select_color_from_palette(
+    PaletteIdx::C300,
+    palette::tailwind::SKY,
+));
+pub const GROUP_HEADER_STYLE: Style = Style::new()
+    .fg(select_color_with_default_palette(PaletteIdx::C100))
+    .add_modifier(Modifier::BOLD);
+pub const TOAST_STYLE: Style = Style::new()
+    .fg(select_color_with_default_palette(PaletteIdx::C100))
+    .bg(select_color_with_default_palette(PaletteIdx::C700))
+    .add_modifier(Modifier::BOLD);
 pub const INFO_STYLE: Style = Style::new().fg(select_color_from_palette(
     PaletteIdx::C400,
     palette::tailwind::SKY,
@@ -34,6 +69,39 @@ pub const DEBUG_STYLE: Style = Style::new().fg(select_color_from_palette(
     palette::tailwind::GREEN,
 ));
 
+/// Levels with a color of their own, most severe first
+pub const LEVELS: [&str; 4] = ["ERROR", "WARN", "INFO", "DEBUG"];
+
+pub fn level_style(level: &str) -> Style {
+    match level {
+        "ERROR" => ERROR_STYLE,
+        "WARN" => WARN_STYLE,
+        "INFO" => INFO_STYLE,
+        "DEBUG" => DEBUG_STYLE,
+        _ => Style::new().fg(TEXT_FG_COLOR),
+    }
+}
+
+/// `style` dimmed by how old the log is: untouched for the last 10 seconds, then a step
+/// dimmer past a minute, ten minutes and an hour. RGB colors are scaled; others just get
+/// the DIM modifier.
+pub fn age_faded(style: Style, age_secs: i64) -> Style {
+    let brightness = match age_secs {
+        ..=10 => return style,
+        11..=60 => 0.8,
+        61..=600 => 0.65,
+        601..=3600 => 0.5,
+        _ => 0.4,
+    };
+    match style.fg {
+        Some(Color::Rgb(r, g, b)) => {
+            let scale = |c: u8| (c as f32 * brightness) as u8;
+            style.fg(Color::Rgb(scale(r), scale(g), scale(b)))
+        }
+        _ => style.add_modifier(Modifier::DIM),
+    }
+}
+
 pub enum PaletteIdx {
     #[allow(dead_code)]
     C50,
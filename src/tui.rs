@@ -0,0 +1,68 @@
+use crossterm::event;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{
+            DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        },
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
+};
+use std::io;
+use std::time::Duration;
+
+pub fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enter_tui_mode()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    Terminal::new(backend)
+}
+
+/// Puts the terminal into the state the TUI draws in. Also used to come back from a child
+/// process that had the terminal to itself.
+pub fn enter_tui_mode() -> io::Result<()> {
+    // Enable raw mode to process key events without OS interference.
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    // Enter the alternate screen to not mess with the user's shell history.
+    // Enable mouse capture to receive mouse events.
+    // Enable bracketed paste so pasted text arrives as one event instead of keystrokes.
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    Ok(())
+}
+
+/// Turns mouse reporting on or off while the TUI runs. Off, the terminal's own text
+/// selection works again.
+pub fn set_mouse_capture(enabled: bool) -> io::Result<()> {
+    if enabled {
+        execute!(io::stdout(), EnableMouseCapture)
+    } else {
+        execute!(io::stdout(), DisableMouseCapture)
+    }
+}
+
+pub fn restore_terminal() -> io::Result<()> {
+    let mut stdout = io::stdout();
+
+    // stop the terminal from sending further mouse packets and bracketed pastes
+    execute!(stdout, DisableMouseCapture, DisableBracketedPaste)?;
+
+    // switch back to the normal screen (still in raw mode, no echo)
+    execute!(stdout, LeaveAlternateScreen)?;
+
+    // drain any pending events so nothing is left in the input buffer.
+    while event::poll(Duration::from_millis(0))? {
+        let _ = event::read()?;
+    }
+
+    // finally leave raw mode (echo/canonical back on)
+    disable_raw_mode()?;
+
+    Ok(())
+}
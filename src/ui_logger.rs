@@ -1,13 +1,78 @@
-use log::{Log, Metadata, Record};
-use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use log::{Level, Log, Metadata, Record};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// Entries kept for DEBUG LOGS; older ones are dropped to prevent memory bloat
+const MAX_RECORDS: usize = 50;
+
+/// One of termlog's own log calls, kept whole so DEBUG LOGS can color and filter by its
+/// parts and decide how to show it when drawing
+#[derive(Debug, Clone)]
+pub struct DebugRecord {
+    pub level: Level,
+    pub target: String,
+    pub time: DateTime<Local>,
+    pub message: String,
+}
+
+impl DebugRecord {
+    pub fn new(level: Level, target: &str, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            target: target.to_string(),
+            time: Local::now(),
+            message: message.into(),
+        }
+    }
+
+    /// `[LEVEL] message`, after the local time as `HH:MM:SS.mmm ` when `timestamp` is set
+    pub fn format(&self, timestamp: bool) -> String {
+        let entry = format!("[{}] {}", self.level, self.message);
+        if timestamp {
+            format!("{} {}", self.time.format("%H:%M:%S%.3f"), entry)
+        } else {
+            entry
+        }
+    }
+
+    /// The line written to the `--debug-log` file: full date and the logging module too,
+    /// since it outlives the session
+    pub fn to_file_line(&self) -> String {
+        format!(
+            "{} [{}] {}: {}",
+            self.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
 
 pub struct UiLogger {
-    logs: Arc<Mutex<Vec<String>>>,
+    logs: Arc<Mutex<Vec<DebugRecord>>>,
+    file: Option<Mutex<File>>, // Also gets every record, uncapped
 }
 
 impl UiLogger {
-    pub fn new(logs: Arc<Mutex<Vec<String>>>) -> Self {
-        Self { logs }
+    pub fn new(logs: Arc<Mutex<Vec<DebugRecord>>>) -> Self {
+        Self { logs, file: None }
+    }
+
+    /// Also appends every record to `path`, on top of the capped DEBUG LOGS buffer
+    pub fn with_file(mut self, path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open debug log '{}'", path.display()))?;
+        self.file = Some(Mutex::new(file));
+        Ok(self)
     }
 }
 
@@ -17,15 +82,85 @@ impl Log for UiLogger {
     }
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            let log_entry = format!("[{}] {}", record.level(), record.args());
+            let debug_record =
+                DebugRecord::new(record.level(), record.target(), record.args().to_string());
+            if let Some(Ok(mut file)) = self.file.as_ref().map(|file| file.lock()) {
+                // Each line is written as it comes so a crash can't lose the last ones.
+                // Nowhere is left to report a failed write to, so it is dropped.
+                let _ = writeln!(file, "{}", debug_record.to_file_line());
+            }
             if let Ok(mut logs) = self.logs.lock() {
-                logs.push(log_entry);
-                // Keep only the last 50 entries to prevent memory bloat
-                if logs.len() > 50 {
+                logs.push(debug_record);
+                if logs.len() > MAX_RECORDS {
                     logs.remove(0);
                 }
             }
         }
     }
-    fn flush(&self) {}
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(|file| file.lock()) {
+            let _ = file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_record_keeps_structure() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let logger = UiLogger::new(logs.clone());
+        logger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("termlog::app")
+                .args(format_args!("resolved ERROR state"))
+                .build(),
+        );
+        let record = logs.lock().unwrap()[0].clone();
+        assert_eq!(record.level, Level::Warn);
+        assert_eq!(record.target, "termlog::app");
+        assert_eq!(record.message, "resolved ERROR state");
+
+        assert_eq!(record.format(false), "[WARN] resolved ERROR state");
+        let stamped = record.format(true);
+        assert!(
+            Regex::new(r"^\d{2}:\d{2}:\d{2}\.\d{3} \[WARN\] resolved ERROR state$")
+                .unwrap()
+                .is_match(&stamped),
+            "{}",
+            stamped
+        );
+    }
+
+    #[test]
+    fn test_file_gets_every_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("debug.log");
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let logger = UiLogger::new(logs.clone()).with_file(&path).unwrap();
+        for i in 0..MAX_RECORDS + 10 {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("termlog::app")
+                    .args(format_args!("tick {}", i))
+                    .build(),
+            );
+        }
+        logger.flush();
+
+        assert_eq!(logs.lock().unwrap().len(), MAX_RECORDS);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), MAX_RECORDS + 10);
+        assert!(
+            lines[0].ends_with(" [INFO] termlog::app: tick 0"),
+            "{}",
+            lines[0]
+        );
+    }
 }